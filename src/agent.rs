@@ -0,0 +1,145 @@
+//! Bots that play the game through the same actions as the player,
+//! for measuring how winnable the game is and for stress testing.
+
+use crate::{
+    event::Stats,
+    matching::MatchRule,
+    ingredient::Ingredient,
+    sim::{Action, Simulation},
+    timestep::TICK,
+};
+
+/// A customer at the counter waiting for their order.
+#[derive(Clone, Debug)]
+pub struct CustomerObservation {
+    /// a stack that meets the order, in the same order as the plate ingredients
+    pub order: Vec<Ingredient>,
+    /// how the plate is checked against the order the customer asked for
+    pub rule: MatchRule,
+    /// seconds left before the customer leaves angry
+    pub patience: f64,
+}
+
+/// What a player can see of the game.
+#[derive(Clone, Debug)]
+pub struct Observation {
+    /// the ingredient queue, next to be released first
+    pub queue: Vec<Ingredient>,
+    /// the ingredients on each plate from the bottom up, plate 0 is the bin
+    pub plates: Vec<Vec<Ingredient>>,
+    /// the plate the chef is over
+    pub active: usize,
+    /// the ingredient put aside in the hold slot
+    pub held: Option<Ingredient>,
+    /// if holding is allowed, it is once per release
+    pub can_hold: bool,
+    pub customers: Vec<CustomerObservation>,
+}
+
+impl Observation {
+    pub fn new(sim: &Simulation) -> Observation {
+        let machine = sim.machine();
+        let line = sim.customer_line();
+        Observation {
+            queue: machine.queue().ingredients.iter().rev().copied().collect(),
+            plates: machine.plates().iter().map(|p| p.ingredients.iter().copied().collect()).collect(),
+            active: machine.active(),
+            held: machine.held(),
+            can_hold: machine.can_hold(),
+            customers: line.customers().iter()
+                .take(line.active_customers())
+                .filter(|c| c.waiting())
+                .map(|c| CustomerObservation {
+                    order: c.rule().example(&c.order().iter().copied().collect::<Vec<_>>(),
+                                            &sim.rules().ingredients),
+                    rule: c.rule(),
+                    patience: c.wait_max() - c.wait_time(),
+                })
+                .collect(),
+        }
+    }
+}
+
+pub trait Agent {
+    /// the next action to take, or none to wait
+    fn act(&mut self, obs: &Observation) -> Option<Action>;
+}
+
+/// Puts each ingredient on the plate where it continues the order of the
+/// least patient customer, and bins anything nobody wants.
+pub struct GreedyAgent;
+
+impl Agent for GreedyAgent {
+    fn act(&mut self, obs: &Observation) -> Option<Action> {
+        let next = *obs.queue.first()?;
+        if obs.customers.is_empty() {
+            return None;
+        }
+        let mut best: Option<(usize, f64)> = None;
+        for (p, plate) in obs.plates.iter().enumerate().skip(1) {
+            // this plate is done and waiting to be served
+            if obs.customers.iter().any(|c| c.order == *plate) {
+                continue;
+            }
+            for c in obs.customers.iter() {
+                if c.order.len() > plate.len() && c.order.starts_with(plate)
+                    && c.order[plate.len()] == next
+                    && best.is_none_or(|(_, patience)| c.patience < patience) {
+                    best = Some((p, c.patience));
+                }
+            }
+        }
+        if let Some((p, _)) = best {
+            return Some(move_then(obs, p, Action::Release));
+        }
+        // a plate that can't become any order is cleared a layer at a time
+        let dead = obs.plates.iter().enumerate().skip(1).find(|(_, plate)| {
+            !obs.customers.iter().any(|c| c.order.starts_with(plate))
+        });
+        if let Some((p, _)) = dead {
+            return Some(move_then(obs, p, Action::Bin));
+        }
+        Some(move_then(obs, 0, Action::Release))
+    }
+}
+
+/// switch towards `plate` the short way round, or do `action` if already there
+fn move_then(obs: &Observation, plate: usize, action: Action) -> Action {
+    if obs.active == plate {
+        return action;
+    }
+    let count = obs.plates.len() as i32;
+    let right = (plate as i32 - obs.active as i32).rem_euclid(count);
+    if right <= count - right {
+        Action::Switch(1)
+    } else {
+        Action::Switch(-1)
+    }
+}
+
+/// Play until the game is over or `time_limit` seconds have passed,
+/// letting `agent` act at most once every `action_delay` seconds.
+/// Each day starts as soon as the last one is over.
+/// The game's events are added to `stats`, returns the seconds played.
+pub fn play(sim: &mut Simulation, agent: &mut dyn Agent, action_delay: f64, time_limit: f64,
+            stats: &mut Stats) -> f64 {
+    let mut time = 0.0;
+    let mut since_action = action_delay;
+    while !sim.is_over() && time < time_limit {
+        sim.next_day();
+        let mut action = None;
+        if since_action >= action_delay {
+            action = agent.act(&Observation::new(sim));
+            if action.is_some() {
+                since_action = 0.0;
+            }
+        }
+        sim.step(TICK, action.as_slice());
+        for e in sim.events() {
+            stats.record(e);
+        }
+        time += TICK;
+        since_action += TICK;
+    }
+    time
+}
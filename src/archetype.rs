@@ -0,0 +1,91 @@
+//! Kinds of customer, defined in `resources/data/archetypes.json`.
+
+use std::{collections::BTreeMap, path::Path};
+
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::data::{load_json, pick_weighted, DataError};
+
+/// A kind of customer, the index of its definition in the `ArchetypeTable`.
+#[derive(Hash, Eq, PartialEq, Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Archetype(pub usize);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ArchetypeDef {
+    pub id: String,
+    pub name: String,
+    pub sprite: String,
+    /// how likely the archetype is to turn up compared to the others
+    pub weight: f64,
+    /// multiplies the patience from the difficulty curve
+    pub patience: f64,
+    /// added to the request size from the difficulty curve
+    pub extra_ingredients: f64,
+    /// added to the score when served
+    pub points: u64,
+    /// lives lost when leaving angry
+    pub life_penalty: u32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ArchetypeTable {
+    defs: Vec<ArchetypeDef>,
+}
+
+impl ArchetypeTable {
+    pub fn new(defs: Vec<ArchetypeDef>) -> Result<ArchetypeTable, String> {
+        if defs.is_empty() {
+            return Err("there must be at least one archetype".to_string());
+        }
+        if defs.iter().any(|d| d.weight < 0.0) || defs.iter().map(|d| d.weight).sum::<f64>() <= 0.0 {
+            return Err("archetype weights must be positive".to_string());
+        }
+        if defs.iter().any(|d| d.patience <= 0.0) {
+            return Err("archetype patience must be above zero".to_string());
+        }
+        for (i, d) in defs.iter().enumerate() {
+            if defs[..i].iter().any(|other| other.id == d.id) {
+                return Err(format!("archetype {} is defined twice", d.id));
+            }
+        }
+        Ok(ArchetypeTable { defs })
+    }
+
+    pub fn load(path: &Path) -> Result<ArchetypeTable, DataError> {
+        Self::new(load_json(path)?)
+            .map_err(|e| DataError::Invalid(path.display().to_string(), e))
+    }
+
+    pub fn get(&self, archetype: Archetype) -> &ArchetypeDef {
+        &self.defs[archetype.0]
+    }
+
+    pub fn find(&self, id: &str) -> Option<Archetype> {
+        self.defs.iter().position(|d| d.id == id).map(Archetype)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Archetype, &ArchetypeDef)> {
+        self.defs.iter().enumerate().map(|(i, d)| (Archetype(i), d))
+    }
+
+    /// the archetype of customers that don't say otherwise
+    pub fn base(&self) -> Archetype {
+        Archetype(0)
+    }
+
+    /// pick an archetype, with the weights in `spawn_table` by id
+    /// instead of the archetypes' own if it isn't empty
+    pub fn random<R: Rng + ?Sized>(&self, rng: &mut R, spawn_table: &BTreeMap<String, f64>) -> Archetype {
+        let weights: Vec<(usize, f64)> = self.defs.iter().enumerate()
+            .map(|(i, d)| (i, if spawn_table.is_empty() {
+                d.weight
+            } else {
+                spawn_table.get(&d.id).copied().unwrap_or(0.0)
+            }))
+            .collect();
+        Archetype(pick_weighted(rng, &weights))
+    }
+}
@@ -0,0 +1,6 @@
+//! Command line argument helpers shared by the game and the `simulate` binary.
+
+/// the value following `name` in the command line arguments
+pub fn arg_value(args: &[String], name: &str) -> Option<String> {
+    args.iter().position(|a| a == name).and_then(|i| args.get(i + 1)).cloned()
+}
@@ -1,31 +1,65 @@
-use std::{collections::VecDeque, path::Path};
+use std::collections::VecDeque;
 
-use crate::{sandwitch::{Ingredients, SandwitchMachine, Sandwitch, SandwitchRender, get_rand_ingredient}, moving_target::Target};
+use crate::{sandwitch::{SandwitchMachine, Sandwitch}, moving_target::Target, geometry::Vec2, sim::GameRng, event::GameEvent, ingredient::Ingredient, rules::Rules, difficulty::Difficulty, level::ScriptedCustomer, archetype::{Archetype, ArchetypeDef}, recipe::Recipe, matching::MatchRule};
 
-use nze_game_sdl::{Camera, geometry::{Vec2, Rect}, GameObject, Render, Error, Colour};
 use rand::prelude::*;
+use serde::{Deserialize, Serialize};
 
-struct Customer {
-    ings: VecDeque<Ingredients>,
-    sandwitch: Option<Sandwitch>,
+#[derive(Serialize, Deserialize)]
+pub struct Customer {
+    pub(crate) ings: VecDeque<Ingredient>,
+    pub(crate) sandwitch: Option<Sandwitch>,
     finished: bool,
-    target: Target,
-    waiting: bool,
-    wait_max: f64,
-    wait_time: f64,
+    pub(crate) target: Target,
+    pub(crate) waiting: bool,
+    pub(crate) wait_max: f64,
+    pub(crate) wait_time: f64,
     max_request_delta: f64,
+    pub(crate) archetype: Archetype,
+    /// the order set by a level script, used instead of a random one
+    planned: Option<Vec<Ingredient>>,
+    /// the recipe ordered by name, if any
+    pub(crate) recipe: Option<Recipe>,
+    pub(crate) rule: MatchRule,
+    /// how well the sandwitch the customer left with met the order
+    pub(crate) quality: f64,
 }
 
 const MIN_REQUEST_SIZE: f64 = 2.0;
-const MAX_REQUEST_DELTA: f64 = 3.0;
-const INITIAL_WAIT_TIME: f64 = 45.0;
-const INITIAL_SPAWN_TIME: f64 = 15.0;
-pub const INITIAL_LIVES: u32 = 3;
-const ACTIVE_CUSTOMERS: usize = 3;
+/// The least quality a customer accepts a served plate with.
+pub(crate) const MIN_SERVE_QUALITY: f64 = 0.5;
+/// The tip as a share of the price when served with full patience.
+const TIP_RATE: f64 = 0.5;
+/// Every this many serves in a row adds `STREAK_BONUS` to the score multiplier.
+const STREAK_STEP: u32 = 3;
+const STREAK_BONUS: f64 = 0.5;
+const MAX_MULTIPLIER: f64 = 3.0;
+/// The score shown for each point in the data files, so a near miss
+/// or a streak bonus on a one point customer still adds something.
+const POINT_SCALE: u64 = 10;
+/// taken from the score when a plate is handed to a customer it is too far from
+const WRONG_DELIVERY_POINTS: u64 = 1;
+/// seconds of patience a wrong delivery costs the customer
+const WRONG_DELIVERY_WAIT: f64 = 5.0;
 
 impl Customer {
     
-    pub fn new(score: f64) -> Customer {
+    /// `served` is how many customers have been served so far
+    pub fn new(served: f64, difficulty: &Difficulty, archetype: Archetype, def: &ArchetypeDef) -> Customer {
+        Self::with_patience(difficulty.wait_time.at(served) * def.patience,
+                            (difficulty.request_delta.at(served) + def.extra_ingredients).max(0.0),
+                            archetype)
+    }
+
+    pub fn scripted(script: &ScriptedCustomer) -> Customer {
+        let mut c = Self::with_patience(script.patience, 0.0, script.archetype);
+        c.planned = Some(script.order.clone());
+        c.recipe = script.recipe;
+        c.rule = script.rule;
+        c
+    }
+
+    fn with_patience(wait_max: f64, max_request_delta: f64, archetype: Archetype) -> Customer {
        let mut c =  Customer {
            ings: VecDeque::new(),
            sandwitch: None,
@@ -33,40 +67,73 @@ impl Customer {
            target: Target::new(),
            waiting: false,
            wait_time: 0.0,
-           wait_max: (INITIAL_WAIT_TIME - (score as f64 * 0.8)).max(15.0),
-           max_request_delta: MAX_REQUEST_DELTA + (score * 0.2),
+           wait_max,
+           max_request_delta,
+           archetype,
+           planned: None,
+           recipe: None,
+           rule: MatchRule::Exact,
+           quality: 0.0,
        };
         c.target.breath = true;
         c
     }
 
-    pub fn populate(&mut self, rng: &mut ThreadRng) {
-        let size = ((rng.gen::<f64>() as f64 * self.max_request_delta) + MIN_REQUEST_SIZE).round() as usize;
+    pub fn populate<R: Rng + ?Sized>(&mut self, rng: &mut R, rules: &Rules) {
+        if let Some(order) = self.planned.take() {
+            self.ings = order.into();
+            return;
+        }
+        if rules.preset.recipe_chance > 0.0 && !rules.recipes.is_empty()
+            && rng.gen::<f64>() < rules.preset.recipe_chance {
+            let recipe = rules.recipes.random(rng);
+            let def = rules.recipes.get(recipe);
+            let mut order = def.stack.clone();
+            def.rule.shape_order(&mut order);
+            self.ings = order.into();
+            self.recipe = Some(recipe);
+            self.rule = def.rule;
+            return;
+        }
+        let book = &rules.ingredients;
+        let size = ((rng.gen::<f64>() * self.max_request_delta) + MIN_REQUEST_SIZE).round() as usize;
         for i in 0..size {
-            if i == 0 || i == size - 1 {
-                self.ings.push_front(Ingredients::Bread);
+            if i == size - 1 {
+                // the bottom, which every plate starts with
+                self.ings.push_front(book.base_bun());
+            } else if i == 0 {
+                self.ings.push_front(book.random_bun(rng));
             } else {
-                let mut ing = get_rand_ingredient(rng);
-                if ing == Ingredients::Bread {
-                    ing = get_rand_ingredient(rng);
+                let mut ing = book.random(rng);
+                if book.is_bun(ing) {
+                    ing = book.random(rng);
                 }
                 self.ings.push_front(ing);
             }
         }
+        if !rules.preset.match_rules.is_empty() {
+            let mut order: Vec<Ingredient> = self.ings.iter().copied().collect();
+            self.rule = MatchRule::random(rng, &rules.preset.match_rules, &order, book);
+            self.rule.shape_order(&mut order);
+            self.ings = order.into();
+        }
     }
 
     pub fn request_met(&mut self, sw: &Sandwitch) -> bool {
-        if sw.ingredients.len() != self.ings.len() {
+        if self.plate_quality(sw) < 1.0 {
             return false;
         }
-        for (i, ing) in self.ings.iter().enumerate() {
-            if !sw.ing_targets[i].is_active() {
-                return false;
-            }
-            if *ing != sw.ingredients[i] { return false; }
-        }
         self.finished = true;
-        return true;
+        true
+    }
+
+    /// how close `sw` is to the order from 0 to 1, 0 while ingredients are still falling
+    pub fn plate_quality(&mut self, sw: &Sandwitch) -> f64 {
+        if !sw.settled() {
+            return 0.0;
+        }
+        let plate: Vec<Ingredient> = sw.ingredients.iter().copied().collect();
+        self.rule.quality(self.ings.make_contiguous(), &plate)
     }
 
     pub fn update(&mut self, dt: f64) {
@@ -79,58 +146,137 @@ impl Customer {
     pub fn waited_too_long(&self) -> bool {
         self.wait_time > self.wait_max
     }
-}
 
-const MAX_CUSTOMERS: usize = 6;
+    /// the requested sandwitch, in the same order as `Sandwitch::ingredients`
+    pub fn order(&self) -> &VecDeque<Ingredient> {
+        &self.ings
+    }
 
+    /// seconds waited at the counter
+    pub fn wait_time(&self) -> f64 {
+        self.wait_time
+    }
+
+    /// seconds the customer will wait before leaving angry
+    pub fn wait_max(&self) -> f64 {
+        self.wait_max
+    }
+
+    pub fn rule(&self) -> MatchRule {
+        self.rule
+    }
+
+    pub fn recipe(&self) -> Option<Recipe> {
+        self.recipe
+    }
+
+    pub fn archetype(&self) -> Archetype {
+        self.archetype
+    }
+
+    /// whether the customer has reached the counter
+    pub fn waiting(&self) -> bool {
+        self.waiting
+    }
+}
+
+#[derive(Serialize, Deserialize)]
 pub struct CustomerLine {
-    active_customers: usize,
-    customers: Vec<Customer>,
-    leaving_customers: Vec<Customer>,
-    angry_customers: Vec<Customer>,
-    rng: ThreadRng,
+    pub(crate) active_customers: usize,
+    pub(crate) customers: Vec<Customer>,
+    pub(crate) leaving_customers: Vec<Customer>,
+    pub(crate) angry_customers: Vec<Customer>,
+    rng: GameRng,
     time_since_customer: f64,
     next_customer_delay: f64,
+    /// points from the customers served
     score: u64,
+    served: u64,
+    /// cents taken, including tips
+    pub(crate) earnings: u64,
+    tips: u64,
+    /// serves since a customer last left angry or an ingredient was binned
+    streak: u32,
+    /// the active customer plates are handed to when serving manually
+    selected: usize,
     lives: u32,
+    /// seconds since the shift started
+    elapsed: f64,
+    /// closing time, no new customers come until the line is opened again
+    closed: bool,
+    /// added to the customers served when reading the difficulty curves,
+    /// so each day starts harder than the last
+    day_offset: f64,
+    /// multiplies the patience of endless customers, raised by upgrades
+    patience: f64,
+    /// the next customer of the level script to arrive
+    next_scripted: usize,
+    #[serde(skip)]
+    events: Vec<GameEvent>,
 }
 
 impl CustomerLine {
-    pub fn new() -> CustomerLine {
+    pub fn new(rng: GameRng, rules: &Rules) -> CustomerLine {
+        let spawn_delay = rules.preset.difficulty.spawn_delay.at(0.0);
         let mut line = CustomerLine {
-            active_customers: ACTIVE_CUSTOMERS,
+            active_customers: rules.preset.active_customers,
             customers: vec![],
             leaving_customers: Vec::new(),
             angry_customers: Vec::new(),
-            rng: thread_rng(),
-            time_since_customer: INITIAL_SPAWN_TIME / 2.0,
-            next_customer_delay: INITIAL_SPAWN_TIME,
+            rng,
+            time_since_customer: spawn_delay / 2.0,
+            next_customer_delay: spawn_delay,
             score: 0,
-            lives: INITIAL_LIVES,
+            served: 0,
+            earnings: 0,
+            tips: 0,
+            streak: 0,
+            selected: 0,
+            lives: rules.preset.lives,
+            elapsed: 0.0,
+            closed: false,
+            day_offset: 0.0,
+            patience: 1.0,
+            next_scripted: 0,
+            events: Vec::new(),
         };
-        line.populate_customers();
+        line.populate_customers(rules);
         line
     }
 
-    fn add_customer(&mut self) {
-        self.customers.push(Customer::new(self.score as f64));
+    fn add_customer(&mut self, rules: &Rules) {
+        self.customers.push(match &rules.level {
+            Some(level) => {
+                self.next_scripted += 1;
+                Customer::scripted(&level.customers[self.next_scripted - 1])
+            },
+            None => {
+                let archetype = rules.archetypes.random(&mut self.rng, &rules.preset.spawn_table);
+                let mut c = Customer::new(self.progress(), &rules.preset.difficulty,
+                                          archetype, rules.archetypes.get(archetype));
+                c.wait_max *= self.patience;
+                c
+            },
+        });
         self.customers.last_mut().unwrap().target.breath_speed = self.rng.gen::<f64>() * 0.1 + 1.0;
         self.customers.last_mut().unwrap().target.breath_size.y = self.rng.gen::<f64>() * 0.1 + 1.0;
-        self.populate_customers();
+        self.populate_customers(rules);
     }
 
-    pub fn update(&mut self, dt: f64) {
+    pub fn update(&mut self, dt: f64, rules: &Rules) {
         self.time_since_customer += dt;
-        if self.time_since_customer > self.next_customer_delay && self.customers.len() < MAX_CUSTOMERS {
+        self.elapsed += dt;
+        if !self.closed && self.customer_due(rules)
+            && self.customers.len() < rules.preset.max_customers {
             self.time_since_customer = 0.0;
-            self.add_customer();
+            self.add_customer(rules);
         }
         let mut to_remove = None;
         for (i, c) in self.customers.iter_mut().enumerate() {
             if c.waiting {
                 c.target.breath_update(dt);
             }
-            if c.waiting && self.leaving_customers.len() > 0 { continue; }
+            if c.waiting && !self.leaving_customers.is_empty() { continue; }
             if c.target.get_pos() == Vec2::zero() {
                 c.target.set_target(CUSTOMER_START)
             }
@@ -141,8 +287,9 @@ impl CustomerLine {
                 ),
                 CUSTOMER_BASE.y + CUSTOMER_OFFSET.y
             ));
-            if c.target.is_active() {
+            if c.target.is_active() && !c.waiting {
                 c.waiting = true;
+                self.events.push(GameEvent::CustomerArrived);
             }
             c.update(dt);
             if c.waited_too_long() {
@@ -150,12 +297,11 @@ impl CustomerLine {
             }
         }
 
-        match to_remove {
-            Some(i) => {
-                self.angry_customers.push(self.customers.remove(i));
-                self.populate_customers();
-            },
-            None => (),
+        if let Some(i) = to_remove {
+            self.angry_customers.push(self.customers.remove(i));
+            self.events.push(GameEvent::CustomerLeftAngry);
+            self.break_streak();
+            self.populate_customers(rules);
         }
 
         let mut c_i = 0;
@@ -184,9 +330,12 @@ impl CustomerLine {
             self.angry_customers[angry_i].target.set_target(CUSTOMER_END);
             self.angry_customers[angry_i].update(dt);
             if self.angry_customers[angry_i].target.is_active() {
-                self.angry_customers.remove(angry_i);
-                if self.lives > 0 {
-                    self.lives -= 1;
+                let angry = self.angry_customers.remove(angry_i);
+                // a customer with no penalty just walks off
+                let lost = rules.archetypes.get(angry.archetype).life_penalty.min(self.lives);
+                if lost > 0 {
+                    self.lives -= lost;
+                    self.events.push(GameEvent::LifeLost { lost, lives: self.lives });
                 }
             } else  {
                 angry_i += 1;
@@ -194,127 +343,343 @@ impl CustomerLine {
         }
     }
 
-    fn populate_customers(&mut self) {
+    /// a scripted customer arrives at its time, or as soon as there is room in line
+    fn customer_due(&self, rules: &Rules) -> bool {
+        match &rules.level {
+            Some(level) => level.customers.get(self.next_scripted)
+                .is_some_and(|c| self.elapsed >= c.time),
+            None => self.time_since_customer > self.next_customer_delay,
+        }
+    }
+
+    /// if every customer of the level script has come and gone
+    pub fn script_finished(&self, rules: &Rules) -> bool {
+        match &rules.level {
+            Some(level) => self.next_scripted >= level.customers.len() && self.is_empty(),
+            None => false,
+        }
+    }
+
+    /// if nobody is in line or on their way out
+    pub fn is_empty(&self) -> bool {
+        self.customers.is_empty() && self.leaving_customers.is_empty() && self.angry_customers.is_empty()
+    }
+
+    /// stop new customers coming, those in line still wait to be served
+    pub(crate) fn close(&mut self) {
+        self.closed = true;
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// let customers come again, `day_offset` further along the difficulty curves
+    pub(crate) fn open(&mut self, day_offset: f64, difficulty: &Difficulty) {
+        self.closed = false;
+        self.day_offset = day_offset;
+        self.next_customer_delay = difficulty.spawn_delay.at(self.progress());
+        self.time_since_customer = self.next_customer_delay / 2.0;
+    }
+
+    pub(crate) fn add_patience(&mut self, extra: f64) {
+        self.patience += extra;
+    }
+
+    /// take up to `points`, in the units of the data files, from the score
+    pub(crate) fn lose_points(&mut self, points: u64) {
+        self.score = self.score.saturating_sub(points * POINT_SCALE);
+    }
+
+    pub(crate) fn add_lives(&mut self, lives: u32) {
+        self.lives += lives;
+    }
+
+    /// how far along the difficulty curves the line is
+    fn progress(&self) -> f64 {
+        self.served as f64 + self.day_offset
+    }
+
+    fn populate_customers(&mut self, rules: &Rules) {
         for i in 0..self.active_customers {
             if self.customers.len() <= i { break; }
-            if self.customers[i].ings.len() == 0 {
-                self.customers[i].populate(&mut self.rng);
+            if self.customers[i].ings.is_empty() {
+                self.customers[i].populate(&mut self.rng, rules);
             }
         }
     }
     
-    pub fn check_machine(&mut self, machine: &mut SandwitchMachine) {
+    pub fn check_machine(&mut self, machine: &mut SandwitchMachine, rules: &Rules) {
         for sw in machine.sandwitches() {
             for i in 0..self.active_customers {
                 if self.customers.len() <= i { break; }
-                if self.customers[i].waiting {
-                    if self.customers[i].request_met(sw) {
-                        self.leaving_customers.push(self.customers.remove(i));
-                        self.leaving_customers.last_mut().unwrap().sandwitch = Some(sw.clone());
-                        sw.reset();
-                        self.populate_customers();
-                        self.add_score();
-                    }
+                if self.customers[i].waiting && self.customers[i].request_met(sw) {
+                    self.serve(i, sw, 1.0, rules);
                 }
             }
         }
     }
 
-    fn add_score(&mut self) {
-        self.score += 1;
-        self.next_customer_delay = (INITIAL_SPAWN_TIME - (self.score as f64 * 0.4)).max(5.0);
+    /// give the active plate to the waiting customer it is closest to,
+    /// a near miss is accepted for fewer points
+    pub fn serve_plate(&mut self, machine: &mut SandwitchMachine, rules: &Rules) {
+        let sw = match machine.active_plate() {
+            Some(sw) => sw,
+            None => return,
+        };
+        let mut best: Option<(usize, f64)> = None;
+        for i in 0..self.active_customers.min(self.customers.len()) {
+            if !self.customers[i].waiting {
+                continue;
+            }
+            let quality = self.customers[i].plate_quality(sw);
+            if best.is_none_or(|(_, q)| quality > q) {
+                best = Some((i, quality));
+            }
+        }
+        match best {
+            Some((i, quality)) if quality >= MIN_SERVE_QUALITY => {
+                self.customers[i].finished = true;
+                self.serve(i, sw, quality, rules);
+            },
+            _ => self.events.push(GameEvent::ServeRefused),
+        }
+    }
+
+    /// give the active plate to the selected customer. A plate too far from
+    /// their order is handed back, costing points, patience and the streak
+    pub fn deliver_plate(&mut self, machine: &mut SandwitchMachine, rules: &Rules) {
+        let sw = match machine.active_plate() {
+            Some(sw) => sw,
+            None => return,
+        };
+        let i = self.selected();
+        // nothing is handed over while the plate is still being built
+        if i >= self.customers.len() || !self.customers[i].waiting || !sw.settled() {
+            self.events.push(GameEvent::ServeRefused);
+            return;
+        }
+        let quality = self.customers[i].plate_quality(sw);
+        if quality >= MIN_SERVE_QUALITY {
+            self.customers[i].finished = true;
+            self.serve(i, sw, quality, rules);
+        } else {
+            self.customers[i].wait_time += WRONG_DELIVERY_WAIT;
+            self.lose_points(WRONG_DELIVERY_POINTS);
+            self.events.push(GameEvent::WrongDelivery);
+            self.break_streak();
+        }
+    }
+
+    /// move the selection along the active customers, negative is to the left
+    pub(crate) fn select_customer(&mut self, diff: i32) {
+        let count = self.active_customers.min(self.customers.len()).max(1) as i32;
+        self.selected = (self.selected() as i32 + diff).rem_euclid(count) as usize;
+    }
+
+    /// the active customer selected for manual serving
+    pub fn selected(&self) -> usize {
+        self.selected.min(self.active_customers.min(self.customers.len()).saturating_sub(1))
+    }
+
+    fn serve(&mut self, i: usize, sw: &mut Sandwitch, quality: f64, rules: &Rules) {
+        let c = &self.customers[i];
+        let full_points = rules.archetypes.get(c.archetype).points;
+        let points = ((full_points * POINT_SCALE) as f64 * quality * self.multiplier()).floor() as u64;
+        // paid for what is on the plate, less for a sloppy one
+        let price = (rules.ingredients.price(sw.ingredients.iter()) as f64 * quality).round() as u64;
+        let patience_left = ((c.wait_max - c.wait_time) / c.wait_max).clamp(0.0, 1.0);
+        let tip = (price as f64 * TIP_RATE * patience_left).round() as u64;
+        self.events.push(GameEvent::OrderServed {
+            size: c.ings.len(),
+            wait: c.wait_time,
+            points,
+            quality,
+            price,
+            tip,
+            streak: self.streak + 1,
+        });
+        self.streak += 1;
+        self.earnings += price + tip;
+        self.tips += tip;
+        self.customers[i].quality = quality;
+        self.leaving_customers.push(self.customers.remove(i));
+        self.leaving_customers.last_mut().unwrap().sandwitch = Some(sw.clone());
+        sw.reset(rules.ingredients.base_bun());
+        self.populate_customers(rules);
+        self.add_score(points, &rules.preset.difficulty);
+    }
+
+    fn add_score(&mut self, points: u64, difficulty: &Difficulty) {
+        self.score += points;
+        self.served += 1;
+        self.next_customer_delay = difficulty.spawn_delay.at(self.progress());
     }
 
     pub fn get_score(&self) -> u64 {
         self.score
     }
 
+    /// end the streak, if there is one
+    pub(crate) fn break_streak(&mut self) {
+        if self.streak > 0 {
+            self.events.push(GameEvent::StreakBroken { streak: self.streak });
+            self.streak = 0;
+        }
+    }
+
+    pub fn streak(&self) -> u32 {
+        self.streak
+    }
+
+    /// what the points of the next serve are multiplied by
+    pub fn multiplier(&self) -> f64 {
+        (1.0 + (self.streak / STREAK_STEP) as f64 * STREAK_BONUS).min(MAX_MULTIPLIER)
+    }
+
+    /// cents taken, including tips
+    pub fn earnings(&self) -> u64 {
+        self.earnings
+    }
+
+    pub fn tips(&self) -> u64 {
+        self.tips
+    }
+
+    /// how many customers have been served
+    pub fn served(&self) -> u64 {
+        self.served
+    }
+
     pub fn lives(&self) -> u32 {
         self.lives
     }
-}
 
-pub struct CustomerRender {
-    customer: GameObject,
-    speech: GameObject,
+    /// the events since this was last called
+    pub fn take_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// the customers in line, only the first `active_customers` have placed an order
+    pub fn customers(&self) -> &[Customer] {
+        &self.customers
+    }
+
+    pub fn active_customers(&self) -> usize {
+        self.active_customers
+    }
 }
+
 const CUSTOMER_START: Vec2 = Vec2::new(500.0, 280.0);
 const CUSTOMER_BASE: Vec2 = Vec2::new(100.0, CUSTOMER_START.y - 5.0);
 const CUSTOMER_END: Vec2 = Vec2::new(CUSTOMER_BASE.x - 25.0, CUSTOMER_BASE.y + 150.0);
-const CUSTOMER_ING_SIZE: Vec2 = Vec2::new(24.0, 12.0);
-const CUSTOMER_ING_OFFSET: Vec2 = Vec2::new(27.0, 30.0);
-const CUSTOMER_ING_SPACING: f64 = -CUSTOMER_ING_SIZE.y * 0.5;
 const CUSTOMER_SIZE: Vec2 = Vec2::new(90.0, 0.0);
-const CUSTOMER_OFFSET: Vec2 = Vec2::new(-20.0, 20.0);
-
-const CUSTOMER_SPEECH_OFFSET: Vec2 = Vec2::new(2.0, 25.0);
+pub(crate) const CUSTOMER_OFFSET: Vec2 = Vec2::new(-20.0, 20.0);
 
-const CUSTOMER_PATIENCE_OFFSET: Rect = Rect::new(15.0, -5.0, 30.0, 5.0);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::QueueGenerator;
 
-impl CustomerRender {
-    pub fn new(render: &mut Render) -> Result<CustomerRender, Error> {
-        Ok(CustomerRender {
-            customer: GameObject::new_from_tex(
-                render.texture_manager.load(
-                    Path::new("resources/textures/customer.png"))?
-            ),
-            speech: GameObject::new_from_tex(
-                render.texture_manager.load(
-                    Path::new("resources/textures/speech.png"))?
-            ),
-        })
-    }
-    
-    pub fn draw(&self, cam: &mut Camera, customers: &mut CustomerLine, sw_render: &SandwitchRender) {
-        for c in customers.customers.iter_mut() {
-            self.draw_customer(cam, c);
-            if c.waiting {
-                self.draw_patience(cam, c);
-            }
-        }
-        for i in 0..customers.active_customers {
-            if customers.customers.len() <= i { break; }
-            if !customers.customers[i].waiting {
-                continue;
-            }
-            let pos_abs = customers.customers[i].target.get_pos_no_offset();
-            let mut speech = self.speech.clone();
-            speech.rect.x = pos_abs.x - CUSTOMER_OFFSET.x + CUSTOMER_ING_OFFSET.x + CUSTOMER_SPEECH_OFFSET.x;
-            speech.rect.h = (customers.customers[i].ings.len() as f64 * (CUSTOMER_ING_SIZE.y + CUSTOMER_ING_SPACING)) + 25.0;
-            speech.rect.y = (pos_abs.y - CUSTOMER_OFFSET.y - speech.rect.h) + CUSTOMER_SPEECH_OFFSET.y + CUSTOMER_ING_OFFSET.y;
-            cam.draw(&speech);
-            sw_render.render_ings(cam, customers.customers[i].ings.iter(),
-                                  Vec2::new(pos_abs.x - CUSTOMER_OFFSET.x,
-                                            pos_abs.y - CUSTOMER_OFFSET.y + CUSTOMER_ING_OFFSET.y),
-                                       CUSTOMER_ING_OFFSET.x + 13.0,
-                                       CUSTOMER_ING_SIZE, CUSTOMER_ING_SPACING, -1.0);
+    /// the points of serving a one point customer a near miss with `streak` serves before it
+    fn near_miss_points(streak: u32) -> u64 {
+        let rules = Rules::builtin();
+        let mut line = CustomerLine::new(GameRng::seed_from_u64(0), &rules);
+        line.add_customer(&rules);
+        line.customers[0].archetype = Archetype(0);
+        assert_eq!(rules.archetypes.get(Archetype(0)).points, 1);
+        line.streak = streak;
+        let mut sw = Sandwitch::new();
+        line.serve(0, &mut sw, 0.7, &rules);
+        match line.take_events().into_iter().find(|e| matches!(e, GameEvent::OrderServed { .. })) {
+            Some(GameEvent::OrderServed { points, .. }) => points,
+            _ => panic!("no serve"),
         }
-        for c in customers.leaving_customers.iter_mut() {
-            self.draw_customer(cam, c);
-            sw_render.render_sw(cam, c.sandwitch.as_ref().unwrap())
+    }
+
+    /// a line of two waiting customers with orders, and a machine over plate 1
+    fn manual_line(rules: &Rules) -> (CustomerLine, SandwitchMachine) {
+        let mut line = CustomerLine::new(GameRng::seed_from_u64(0), rules);
+        line.add_customer(rules);
+        line.add_customer(rules);
+        line.populate_customers(rules);
+        for c in line.customers.iter_mut() {
+            c.waiting = true;
         }
-        for c in customers.angry_customers.iter() {
-            self.draw_customer(cam, c);
+        let mut machine = SandwitchMachine::new(GameRng::seed_from_u64(0), &rules.ingredients,
+                                                rules.preset.queue_size, QueueGenerator::default());
+        machine.switch(1);
+        (line, machine)
+    }
+
+    /// put `ings` on the active plate, already settled
+    fn set_plate(machine: &mut SandwitchMachine, ings: impl IntoIterator<Item = Ingredient>) {
+        let mut sw = Sandwitch::new();
+        for ing in ings {
+            sw.add_back(ing, Target::new());
         }
+        *machine.active_plate().unwrap() = sw;
+    }
+
+    #[test]
+    fn a_wrong_delivery_is_handed_back() {
+        let rules = Rules::builtin();
+        let (mut line, mut machine) = manual_line(&rules);
+        line.score = 100;
+        line.streak = 4;
+        set_plate(&mut machine, [rules.ingredients.base_bun()]);
+        let wait = line.customers[0].wait_time;
+        line.deliver_plate(&mut machine, &rules);
+        assert_eq!(line.score, 100 - WRONG_DELIVERY_POINTS * POINT_SCALE);
+        assert_eq!(line.customers[0].wait_time, wait + WRONG_DELIVERY_WAIT);
+        assert_eq!(line.streak, 0);
+        assert_eq!(line.served, 0);
+        assert!(line.customers[0].waiting);
+        let events = line.take_events();
+        assert!(events.contains(&GameEvent::WrongDelivery));
+        assert!(events.contains(&GameEvent::StreakBroken { streak: 4 }));
+    }
+
+    #[test]
+    fn a_good_delivery_serves_the_selected_customer() {
+        let rules = Rules::builtin();
+        let (mut line, mut machine) = manual_line(&rules);
+        line.select_customer(1);
+        assert_eq!(line.selected(), 1);
+        let first: Vec<Ingredient> = line.customers[0].ings.iter().copied().collect();
+        let second: Vec<Ingredient> = line.customers[1].ings.iter().copied().collect();
+        let example = line.customers[1].rule.example(&second, &rules.ingredients);
+        set_plate(&mut machine, example);
+        line.deliver_plate(&mut machine, &rules);
+        assert_eq!(line.served, 1);
+        assert!(line.score > 0);
+        assert_eq!(line.leaving_customers.len(), 1);
+        assert!(line.leaving_customers[0].ings.iter().eq(second.iter()));
+        assert!(line.customers[0].ings.iter().eq(first.iter()));
+        assert!(line.take_events().iter().any(|e| matches!(e, GameEvent::OrderServed { .. })));
+    }
+
+    #[test]
+    fn select_customer_wraps_around_the_active_customers() {
+        let rules = Rules::builtin();
+        let (mut line, _) = manual_line(&rules);
+        let count = line.active_customers.min(line.customers.len());
+        line.select_customer(-1);
+        assert_eq!(line.selected(), count - 1);
+        line.select_customer(1);
+        assert_eq!(line.selected(), 0);
+    }
+
+    #[test]
+    fn near_misses_still_score() {
+        assert!(near_miss_points(0) > 0);
     }
 
-    fn draw_customer(&self, cam: &mut Camera, c: &Customer) {
-        let mut go = self.customer.clone();
-        let pos = c.target.get_pos();
-        go.rect.x = pos.x;
-        go.rect.y = pos.y;
-        cam.draw(&go);
-    }
-
-    fn draw_patience(&self, cam: &mut Camera, c: &Customer) {
-        let mut pos = c.target.get_pos();
-        pos.x += CUSTOMER_PATIENCE_OFFSET.x;
-        pos.y += CUSTOMER_PATIENCE_OFFSET.y;
-        let ratio = c.wait_time / c.wait_max;
-        let length = CUSTOMER_PATIENCE_OFFSET.w * ratio;
-        cam.draw_rect(Rect::new(pos.x, pos.y, CUSTOMER_PATIENCE_OFFSET.w, CUSTOMER_PATIENCE_OFFSET.h),
-                      Colour::new(124, 199, 109, 255), Vec2::new(1.0, 1.0));
-        cam.draw_rect(Rect::new(pos.x, pos.y, length, CUSTOMER_PATIENCE_OFFSET.h),
-                      Colour::new(117, 68, 68, 255), Vec2::new(1.0, 1.0));
+    #[test]
+    fn every_streak_tier_adds_points() {
+        let tiers: Vec<u64> = (0..=4).map(|tier| near_miss_points(tier * STREAK_STEP)).collect();
+        assert!(tiers.windows(2).all(|w| w[0] < w[1]), "{:?}", tiers);
+        // capped at triple
+        assert_eq!(near_miss_points(10 * STREAK_STEP), tiers[4]);
     }
 }
@@ -0,0 +1,54 @@
+//! Loading the json files in `resources/data`.
+
+use std::{fmt, fs::File, io::{self, BufReader}, path::Path};
+
+use rand::Rng;
+use serde::de::DeserializeOwned;
+
+/// The folder the game's data files are loaded from.
+pub const DATA_DIR: &str = "resources/data";
+
+#[derive(Debug)]
+pub enum DataError {
+    Io(String, io::Error),
+    Parse(String, serde_json::Error),
+    /// the file parsed but its contents don't make sense
+    Invalid(String, String),
+}
+
+impl fmt::Display for DataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataError::Io(path, e) => write!(f, "failed to read {}: {}", path, e),
+            DataError::Parse(path, e) => write!(f, "failed to parse {}: {}", path, e),
+            DataError::Invalid(path, e) => write!(f, "invalid data in {}: {}", path, e),
+        }
+    }
+}
+
+impl std::error::Error for DataError {}
+
+pub fn load_json<T: DeserializeOwned>(path: &Path) -> Result<T, DataError> {
+    let name = path.display().to_string();
+    let file = File::open(path).map_err(|e| DataError::Io(name.clone(), e))?;
+    serde_json::from_reader(BufReader::new(file)).map_err(|e| DataError::Parse(name, e))
+}
+
+/// parse one of the data files built into the binary
+pub fn parse_builtin<T: DeserializeOwned>(name: &str, json: &str) -> T {
+    serde_json::from_str(json)
+        .unwrap_or_else(|e| panic!("built in data file {} is invalid: {}", name, e))
+}
+
+/// pick one of the `(index, weight)` pairs, each as likely as its weight
+pub fn pick_weighted<R: Rng + ?Sized>(rng: &mut R, weights: &[(usize, f64)]) -> usize {
+    let total: f64 = weights.iter().map(|(_, w)| w).sum();
+    let mut r = rng.gen::<f64>() * total;
+    for (i, w) in weights {
+        if r < *w {
+            return *i;
+        }
+        r -= w;
+    }
+    weights.last().map_or(0, |(i, _)| *i)
+}
@@ -0,0 +1,131 @@
+//! How the game gets harder as more customers are served, defined per preset in
+//! `resources/data/presets.json` so balancing doesn't need a recompile.
+
+use serde::{Deserialize, Serialize};
+
+/// A value that changes with the progress: the customers served so far,
+/// plus `day_step` for each day played in endless mode.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Curve {
+    /// `base + slope * progress`, kept between `min` and `max` if given
+    Linear {
+        base: f64,
+        slope: f64,
+        #[serde(default)]
+        min: Option<f64>,
+        #[serde(default)]
+        max: Option<f64>,
+    },
+    /// `[progress, value]` points joined by straight lines,
+    /// flat before the first point and after the last
+    Keyframes(Vec<(f64, f64)>),
+}
+
+impl Curve {
+    /// the value after `progress` customers
+    pub fn at(&self, progress: f64) -> f64 {
+        match self {
+            Curve::Linear { base, slope, min, max } => {
+                let mut v = base + slope * progress;
+                if let Some(min) = min {
+                    v = v.max(*min);
+                }
+                if let Some(max) = max {
+                    v = v.min(*max);
+                }
+                v
+            },
+            Curve::Keyframes(points) => {
+                let after = points.iter().position(|(p, _)| *p > progress).unwrap_or(points.len());
+                if after == 0 {
+                    return points[0].1;
+                }
+                if after == points.len() {
+                    return points[after - 1].1;
+                }
+                let (p0, v0) = points[after - 1];
+                let (p1, v1) = points[after];
+                v0 + (v1 - v0) * (progress - p0) / (p1 - p0)
+            },
+        }
+    }
+
+    fn validate(&self, name: &str) -> Result<(), String> {
+        match self {
+            Curve::Linear { min: Some(min), max: Some(max), .. } if min > max =>
+                Err(format!("{} has a min above its max", name)),
+            Curve::Keyframes(points) if points.is_empty() =>
+                Err(format!("{} has no keyframes", name)),
+            Curve::Keyframes(points) if points.windows(2).any(|w| w[0].0 >= w[1].0) =>
+                Err(format!("{} keyframes must be in increasing progress order", name)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The curves that set how hard the game is at each point of progress.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Difficulty {
+    /// seconds a customer waits before leaving angry
+    pub wait_time: Curve,
+    /// how many more ingredients than the smallest order a customer can ask for
+    pub request_delta: Curve,
+    /// seconds between customers arriving
+    pub spawn_delay: Curve,
+}
+
+impl Difficulty {
+    pub fn validate(&self) -> Result<(), String> {
+        self.wait_time.validate("wait_time")?;
+        self.request_delta.validate("request_delta")?;
+        self.spawn_delay.validate("spawn_delay")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keyframes() -> Curve {
+        Curve::Keyframes(vec![(0.0, 45.0), (20.0, 30.0), (40.0, 15.0)])
+    }
+
+    #[test]
+    fn keyframes_are_joined_by_lines() {
+        let curve = keyframes();
+        assert_eq!(curve.at(0.0), 45.0);
+        assert_eq!(curve.at(10.0), 37.5);
+        assert_eq!(curve.at(20.0), 30.0);
+        assert_eq!(curve.at(35.0), 18.75);
+    }
+
+    #[test]
+    fn keyframes_are_flat_outside_the_first_and_last() {
+        let curve = Curve::Keyframes(vec![(5.0, 10.0), (15.0, 20.0)]);
+        assert_eq!(curve.at(0.0), 10.0);
+        assert_eq!(curve.at(-3.0), 10.0);
+        assert_eq!(curve.at(15.0), 20.0);
+        assert_eq!(curve.at(100.0), 20.0);
+        assert_eq!(Curve::Keyframes(vec![(3.0, 7.0)]).at(50.0), 7.0);
+    }
+
+    #[test]
+    fn lines_are_clamped() {
+        let curve = Curve::Linear { base: 45.0, slope: -1.0, min: Some(15.0), max: Some(40.0) };
+        assert_eq!(curve.at(0.0), 40.0);
+        assert_eq!(curve.at(10.0), 35.0);
+        assert_eq!(curve.at(100.0), 15.0);
+        assert_eq!(Curve::Linear { base: 2.0, slope: 0.5, min: None, max: None }.at(100.0), 52.0);
+    }
+
+    #[test]
+    fn validate_rejects_bad_curves() {
+        assert!(keyframes().validate("curve").is_ok());
+        assert!(Curve::Keyframes(Vec::new()).validate("curve").is_err());
+        assert!(Curve::Keyframes(vec![(0.0, 1.0), (0.0, 2.0)]).validate("curve").is_err());
+        assert!(Curve::Keyframes(vec![(10.0, 1.0), (5.0, 2.0)]).validate("curve").is_err());
+        assert!(Curve::Linear { base: 1.0, slope: 0.0, min: Some(5.0), max: Some(2.0) }.validate("curve").is_err());
+        assert!(Curve::Linear { base: 1.0, slope: 0.0, min: Some(2.0), max: None }.validate("curve").is_ok());
+    }
+}
@@ -0,0 +1,107 @@
+//! Things that happen during a game, for audio, effects and stats to react to.
+
+use serde::{Deserialize, Serialize};
+
+use crate::ingredient::Ingredient;
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum GameEvent {
+    /// a customer reached the counter and started waiting
+    CustomerArrived,
+    /// a customer took a sandwitch of `size` ingredients after waiting `wait` seconds,
+    /// adding `points` to the score, `quality` is 1 for a perfect sandwitch.
+    /// They paid `price` and left a `tip`, both in cents. `streak` is the serves in a row counting this one
+    OrderServed { size: usize, wait: f64, points: u64, quality: f64, price: u64, tip: u64, streak: u32 },
+    /// the served plate wasn't close enough to any order
+    ServeRefused,
+    /// a plate was handed to a customer it was too far from, when serving manually
+    WrongDelivery,
+    /// a customer ran out of patience and left
+    CustomerLeftAngry,
+    /// a streak of `streak` serves in a row ended with an angry customer or a binned ingredient
+    StreakBroken { streak: u32 },
+    /// an angry customer reached the door taking `lost` lives, `lives` are left
+    LifeLost { lost: u32, lives: u32 },
+    /// the next ingredient in the queue was dropped onto `plate`, plate 0 is the bin
+    IngredientReleased { ingredient: Ingredient, plate: usize },
+    /// the top ingredient of `plate` was sent back to the queue
+    IngredientBinned { ingredient: Ingredient, plate: usize },
+    /// the next ingredient was put in the hold slot
+    IngredientHeld { ingredient: Ingredient },
+    /// the latest machine operation was rewound
+    Undone,
+    PlateSwitched { from: usize, to: usize },
+    /// closing time came and the last customer of `day` left
+    DayEnded { day: u32 },
+}
+
+/// Totals of the events of a game.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct Stats {
+    pub served: u64,
+    /// served sandwitches that didn't quite meet the order
+    pub sloppy: u64,
+    pub refused: u64,
+    /// plates handed to the wrong customer
+    pub wrong: u64,
+    /// cents taken, including tips
+    pub earnings: u64,
+    pub tips: u64,
+    /// the most serves in a row
+    pub best_streak: u32,
+    pub angry: u64,
+    pub lives_lost: u64,
+    /// seconds served customers waited in total
+    pub total_wait: f64,
+    pub released: u64,
+    /// ingredients dropped into the bin
+    pub wasted: u64,
+    pub binned: u64,
+    pub held: u64,
+    pub undone: u64,
+}
+
+impl Stats {
+    pub fn new() -> Stats {
+        Stats::default()
+    }
+
+    pub fn record(&mut self, event: &GameEvent) {
+        match event {
+            GameEvent::OrderServed { wait, quality, price, tip, streak, .. } => {
+                self.best_streak = self.best_streak.max(*streak);
+                self.served += 1;
+                self.total_wait += wait;
+                self.earnings += price + tip;
+                self.tips += tip;
+                if *quality < 1.0 {
+                    self.sloppy += 1;
+                }
+            },
+            GameEvent::ServeRefused => self.refused += 1,
+            GameEvent::WrongDelivery => self.wrong += 1,
+            GameEvent::CustomerLeftAngry => self.angry += 1,
+            GameEvent::LifeLost { lost, .. } => self.lives_lost += *lost as u64,
+            GameEvent::IngredientReleased { plate, .. } => {
+                self.released += 1;
+                if *plate == 0 {
+                    self.wasted += 1;
+                }
+            },
+            GameEvent::IngredientBinned { .. } => self.binned += 1,
+            GameEvent::IngredientHeld { .. } => self.held += 1,
+            GameEvent::Undone => self.undone += 1,
+            GameEvent::CustomerArrived | GameEvent::PlateSwitched { .. }
+            | GameEvent::StreakBroken { .. } | GameEvent::DayEnded { .. } => (),
+        }
+    }
+
+    /// average seconds a served customer waited
+    pub fn average_wait(&self) -> f64 {
+        if self.served == 0 {
+            0.0
+        } else {
+            self.total_wait / self.served as f64
+        }
+    }
+}
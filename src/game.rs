@@ -0,0 +1,148 @@
+use std::path::Path;
+
+use rand::SeedableRng;
+use nze_game_sdl::{
+    Camera,
+    Render,
+    input::Controls,
+    Error, GameObject, resource::Font,
+};
+
+use crate::{
+    input::Input,
+    render::{SandwitchRender, CustomerRender},
+    replay::InputFrame,
+    event::GameEvent,
+    scene::{SceneStack, Title, Playing, Paused, Transition},
+    sim::{Simulation, GameRng},
+    rules::{Rules, GameData},
+    timestep::FixedStep,
+};
+
+/// Textures and renderers shared by the scenes.
+pub struct Assets {
+    pub sandwitch_render: SandwitchRender,
+    pub customer_render: CustomerRender,
+    pub bg: GameObject,
+    pub font: Font,
+    pub end_screen: GameObject,
+    pub end_sign: GameObject,
+    pub heart: GameObject,
+    pub heart_off: GameObject,
+}
+
+/// The state every scene can see.
+pub struct Context {
+    pub sim: Simulation,
+    /// seeds each new game
+    pub rng: GameRng,
+    pub data: GameData,
+    /// the rules every new game is played with, from the chosen preset
+    pub rules: Rules,
+    /// plates are handed to customers by hand instead of automatically, set in the settings
+    pub manual_serving: bool,
+    pub clock: FixedStep,
+    pub input: Input,
+    /// what happened in the game this frame
+    pub events: Vec<GameEvent>,
+    /// set while a game is being played, so it can be saved
+    pub in_game: bool,
+    pub fullscreen_requested: bool,
+    pub assets: Assets,
+}
+
+impl Context {
+    pub fn new_game(&mut self) {
+        self.sim = Simulation::with_rules(&mut self.rng, self.rules.clone());
+        self.sim.set_manual_serving(self.manual_serving);
+    }
+}
+
+pub struct Game {
+    ctx: Context,
+    scenes: SceneStack,
+}
+
+impl Game {
+    /// every game played in this session is seeded from `seed`
+    pub fn new(render: &mut Render, seed: u64, data: GameData) -> Result<Game, Error> {
+        let rules = data.default_rules();
+        let mut rng = GameRng::seed_from_u64(seed);
+        let mut ctx = Context {
+            sim: Simulation::with_rules(&mut rng, rules.clone()),
+            rng,
+            clock: FixedStep::new(),
+            input: Input::new(),
+            events: Vec::new(),
+            in_game: false,
+            manual_serving: false,
+            fullscreen_requested: false,
+            assets: Assets {
+                sandwitch_render: SandwitchRender::new(render, &data.ingredients)?,
+                customer_render: CustomerRender::new(render, &data.archetypes, &data.recipes)?,
+                bg: GameObject::new_from_tex(render.texture_manager.load(Path::new("resources/textures/restaurant.png"))?),
+                font: render.font_manager.load_font(Path::new("resources/fonts/ShortStack-Regular.ttf"))?,
+                end_screen: GameObject::new_from_tex(render.texture_manager.load(Path::new("resources/textures/end.png"))?),
+                end_sign: GameObject::new_from_tex(render.texture_manager.load(Path::new("resources/textures/sign.png"))?),
+                heart: GameObject::new_from_tex(render.texture_manager.load(Path::new("resources/textures/heart.png"))?),
+                heart_off: GameObject::new_from_tex(render.texture_manager.load(Path::new("resources/textures/heartless.png"))?),
+            },
+            data,
+            rules,
+        };
+        let scenes = SceneStack::new(Box::new(Title::new()), &mut ctx);
+        Ok(Game { ctx, scenes })
+    }
+
+    pub fn input(&self) -> &Input {
+        &self.ctx.input
+    }
+
+    /// the game being played, none once it has ended or before one has started
+    pub fn in_progress(&self) -> Option<&Simulation> {
+        if self.ctx.in_game && !self.ctx.sim.is_over() {
+            Some(&self.ctx.sim)
+        } else {
+            None
+        }
+    }
+
+    /// continue a saved game, starting paused
+    pub fn resume(&mut self, sim: Simulation) {
+        self.ctx.rules = sim.rules().clone();
+        self.ctx.manual_serving = sim.manual_serving();
+        self.ctx.sim = sim;
+        self.scenes.apply(Transition::Clear(Box::new(Playing::new())), &mut self.ctx);
+        self.scenes.apply(Transition::Push(Box::new(Paused::new())), &mut self.ctx);
+    }
+
+    /// if a scene asked for fullscreen to be toggled since this was last called
+    pub fn take_fullscreen_request(&mut self) -> bool {
+        std::mem::take(&mut self.ctx.fullscreen_requested)
+    }
+
+    pub fn update(&mut self, controls: &mut Controls) {
+        self.ctx.input.update(controls);
+        self.update_frame(controls.frame_elapsed);
+    }
+
+    /// update using a recorded frame instead of the controls
+    pub fn play_frame(&mut self, frame: &InputFrame) {
+        self.ctx.input.set_frame(frame);
+        self.update_frame(frame.frame_elapsed);
+    }
+
+    /// the events of the ticks run this frame, for effects and audio
+    pub fn events(&self) -> &[GameEvent] {
+        &self.ctx.events
+    }
+
+    fn update_frame(&mut self, frame_elapsed: f64) {
+        self.ctx.events.clear();
+        self.scenes.update(&mut self.ctx, frame_elapsed);
+    }
+
+    pub fn draw(&mut self, cam: &mut Camera) {
+        self.scenes.draw(&mut self.ctx, cam);
+    }
+}
@@ -0,0 +1,139 @@
+//! How the ingredient queue is filled, chosen by a preset or level.
+
+use std::collections::BTreeMap;
+
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    data::pick_weighted,
+    ingredient::{Ingredient, IngredientBook},
+};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueueGenerator {
+    /// each ingredient as likely as its weight in `ingredients.json`
+    #[default]
+    Weighted,
+    /// every ingredient equally likely
+    Uniform,
+    /// every ingredient once per shuffled bag, so none goes missing for long
+    Bag,
+    /// ingredient ids and their weights, ingredients not listed never come up
+    Table(BTreeMap<String, f64>),
+    /// this chance of an ingredient the waiting customers still need,
+    /// weighted by the ingredient weights otherwise
+    Demand(f64),
+}
+
+impl QueueGenerator {
+    pub fn validate(&self, book: &IngredientBook) -> Result<(), String> {
+        match self {
+            QueueGenerator::Table(table) => {
+                if let Some(id) = table.keys().find(|id| book.find(id).is_none()) {
+                    return Err(format!("queue table has unknown ingredient {}", id));
+                }
+                if table.values().any(|w| *w < 0.0) || table.values().sum::<f64>() <= 0.0 {
+                    return Err("queue table weights must be positive".to_string());
+                }
+            },
+            QueueGenerator::Demand(chance) if !(0.0..=1.0).contains(chance) => {
+                return Err("queue demand chance must be between 0 and 1".to_string());
+            },
+            _ => (),
+        }
+        Ok(())
+    }
+
+    /// the next ingredient for the queue. `bag` is what is left of the current bag
+    /// and `demand` the ingredients the waiting customers still need
+    pub fn next<R: Rng + ?Sized>(&self, rng: &mut R, book: &IngredientBook,
+                                  bag: &mut Vec<Ingredient>, demand: &[Ingredient]) -> Ingredient {
+        match self {
+            QueueGenerator::Weighted => book.random(rng),
+            QueueGenerator::Uniform => Ingredient(rng.gen_range(0..book.iter().count())),
+            QueueGenerator::Bag => {
+                if bag.is_empty() {
+                    bag.extend(book.iter().map(|(i, _)| i));
+                    bag.shuffle(rng);
+                }
+                bag.pop().expect("bag was filled")
+            },
+            QueueGenerator::Table(table) => {
+                let weights: Vec<(usize, f64)> = table.iter()
+                    .filter_map(|(id, w)| book.find(id).map(|i| (i.0, *w)))
+                    .collect();
+                Ingredient(pick_weighted(rng, &weights))
+            },
+            QueueGenerator::Demand(chance) => {
+                if !demand.is_empty() && rng.gen::<f64>() < *chance {
+                    demand[rng.gen_range(0..demand.len())]
+                } else {
+                    book.random(rng)
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{rules::GameData, sim::GameRng};
+
+    fn deal(generator: &QueueGenerator, count: usize, demand: &[Ingredient]) -> Vec<Ingredient> {
+        let book = GameData::builtin().ingredients;
+        let mut rng = GameRng::seed_from_u64(0);
+        let mut bag = Vec::new();
+        (0..count).map(|_| generator.next(&mut rng, &book, &mut bag, demand)).collect()
+    }
+
+    #[test]
+    fn bag_deals_everything_once_per_bag() {
+        let book = GameData::builtin().ingredients;
+        let size = book.iter().count();
+        let all: Vec<Ingredient> = book.iter().map(|(i, _)| i).collect();
+        for bag in deal(&QueueGenerator::Bag, size * 5, &[]).chunks(size) {
+            let mut bag = bag.to_vec();
+            bag.sort_by_key(|i| i.0);
+            assert_eq!(bag, all);
+        }
+    }
+
+    #[test]
+    fn table_only_deals_its_ingredients() {
+        let book = GameData::builtin().ingredients;
+        let ids: Vec<String> = book.iter().skip(1).take(2).map(|(_, d)| d.id.clone()).collect();
+        let table = ids.iter().map(|id| (id.clone(), 1.0)).collect();
+        let dealt = deal(&QueueGenerator::Table(table), 200, &[]);
+        for id in ids.iter() {
+            assert!(dealt.contains(&book.find(id).unwrap()), "{} never dealt", id);
+        }
+        assert!(dealt.iter().all(|i| ids.contains(&book.get(*i).id)));
+    }
+
+    #[test]
+    fn demand_leans_toward_what_is_needed() {
+        let book = GameData::builtin().ingredients;
+        let needed = book.iter().map(|(i, _)| i).last().unwrap();
+        let count = |generator: &QueueGenerator, demand: &[Ingredient]|
+            deal(generator, 1000, demand).iter().filter(|i| **i == needed).count();
+        let weighted = count(&QueueGenerator::Weighted, &[]);
+        let demand = count(&QueueGenerator::Demand(0.5), &[needed]);
+        assert!(demand > weighted + 300, "{} needed of 1000, {} without demand", demand, weighted);
+        // with nothing needed it falls back to the weights
+        assert_eq!(count(&QueueGenerator::Demand(0.5), &[]), weighted);
+    }
+
+    #[test]
+    fn validate_rejects_bad_tables_and_chances() {
+        let book = GameData::builtin().ingredients;
+        let table = |id: &str, w: f64| QueueGenerator::Table([(id.to_string(), w)].into_iter().collect());
+        let id = book.get(Ingredient(0)).id.clone();
+        assert!(table(&id, 1.0).validate(&book).is_ok());
+        assert!(table("no_such_ingredient", 1.0).validate(&book).is_err());
+        assert!(table(&id, 0.0).validate(&book).is_err());
+        assert!(QueueGenerator::Demand(1.5).validate(&book).is_err());
+    }
+}
@@ -0,0 +1,97 @@
+//! Ingredients are defined in `resources/data/ingredients.json`,
+//! so new ones only need a texture and an entry in that file.
+
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::data::{pick_weighted, DataError};
+
+/// An ingredient, the index of its definition in the `IngredientBook`.
+#[derive(Hash, Eq, PartialEq, Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Ingredient(pub usize);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct IngredientDef {
+    pub id: String,
+    /// shown to the player, like in order labels
+    pub name: String,
+    pub texture: String,
+    /// what a customer pays for the ingredient, in cents
+    pub price: u64,
+    /// how likely the ingredient is to be picked compared to the others
+    pub weight: f64,
+    /// if it can go at the top and bottom of a sandwitch
+    pub bun: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct IngredientBook {
+    defs: Vec<IngredientDef>,
+}
+
+impl IngredientBook {
+    pub fn new(defs: Vec<IngredientDef>) -> Result<IngredientBook, String> {
+        if !defs.iter().any(|d| d.bun) {
+            return Err("at least one ingredient must be a bun".to_string());
+        }
+        if defs.iter().any(|d| d.weight < 0.0) || defs.iter().map(|d| d.weight).sum::<f64>() <= 0.0 {
+            return Err("ingredient weights must be positive".to_string());
+        }
+        for (i, d) in defs.iter().enumerate() {
+            if defs[..i].iter().any(|other| other.id == d.id) {
+                return Err(format!("ingredient {} is defined twice", d.id));
+            }
+        }
+        Ok(IngredientBook { defs })
+    }
+
+    pub fn load(path: &std::path::Path) -> Result<IngredientBook, DataError> {
+        Self::new(crate::data::load_json(path)?)
+            .map_err(|e| DataError::Invalid(path.display().to_string(), e))
+    }
+
+    pub fn get(&self, ing: Ingredient) -> &IngredientDef {
+        &self.defs[ing.0]
+    }
+
+    pub fn find(&self, id: &str) -> Option<Ingredient> {
+        self.defs.iter().position(|d| d.id == id).map(Ingredient)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Ingredient, &IngredientDef)> {
+        self.defs.iter().enumerate().map(|(i, d)| (Ingredient(i), d))
+    }
+
+    /// the price of a sandwitch of `ings`, in cents
+    pub fn price<'a>(&self, ings: impl Iterator<Item = &'a Ingredient>) -> u64 {
+        ings.map(|i| self.get(*i).price).sum()
+    }
+
+    pub fn is_bun(&self, ing: Ingredient) -> bool {
+        self.get(ing).bun
+    }
+
+    /// the bun every plate starts with
+    pub fn base_bun(&self) -> Ingredient {
+        Ingredient(self.defs.iter().position(|d| d.bun).expect("book has a bun"))
+    }
+
+    /// pick an ingredient using the weights
+    pub fn random<R: Rng + ?Sized>(&self, rng: &mut R) -> Ingredient {
+        self.pick(rng, |_| true)
+    }
+
+    pub fn random_bun<R: Rng + ?Sized>(&self, rng: &mut R) -> Ingredient {
+        self.pick(rng, |d| d.bun)
+    }
+
+    fn pick<R: Rng + ?Sized>(&self, rng: &mut R, allowed: impl Fn(&IngredientDef) -> bool) -> Ingredient {
+        let weights: Vec<(usize, f64)> = self.defs.iter().enumerate()
+            .filter(|(_, d)| allowed(d))
+            .map(|(i, d)| (i, d.weight))
+            .collect();
+        Ingredient(pick_weighted(rng, &weights))
+    }
+}
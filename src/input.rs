@@ -1,5 +1,7 @@
 use nze_game_sdl::input::{Controls, keyboard::Key, controller::{Button, Side}};
 
+use crate::replay::InputFrame;
+
 enum Dir {
     Up,
     Down,
@@ -18,7 +20,7 @@ impl Joy {
         Joy { dir, side }
     }
     fn check(&self, controls: &Controls) -> bool {
-        let v =  controls.c.joy(0, self.side.clone());
+        let v =  controls.c.joy(0, self.side);
         match self.dir {
             Dir::Up => v.y < -JOY_ACTIVATION,
             Dir::Down => v.y > JOY_ACTIVATION,
@@ -80,6 +82,12 @@ impl Btn {
             self.input
         }
     }
+
+    /// set the button as if it was just pressed or released
+    fn set_pressed(&mut self, pressed: bool) {
+        self.input = pressed;
+        self.prev_input = false;
+    }
 }
 
 pub struct Input {
@@ -89,6 +97,22 @@ pub struct Input {
     pub up: Btn,
     pub pause: Btn,
     pub fullscreen: Btn,
+    /// opens the recipe book
+    pub recipes: Btn,
+    /// serves the active plate to the closest order
+    pub serve: Btn,
+    /// puts the next ingredient aside
+    pub hold: Btn,
+    /// rewinds the latest release, bin, switch or hold
+    pub undo: Btn,
+    /// picks the next customer to serve when serving manually
+    pub select: Btn,
+}
+
+impl Default for Input {
+    fn default() -> Input {
+        Input::new()
+    }
 }
 
 impl Input {
@@ -124,6 +148,31 @@ impl Input {
                 vec![Button::Back],
                 vec![],
             ),
+            recipes: Btn::new(
+                vec![Key::R, Key::Tab],
+                vec![Button::Y],
+                vec![],
+            ),
+            serve: Btn::new(
+                vec![Key::Space, Key::E],
+                vec![Button::X],
+                vec![],
+            ),
+            hold: Btn::new(
+                vec![Key::C, Key::LShift],
+                vec![Button::LeftShoulder],
+                vec![],
+            ),
+            undo: Btn::new(
+                vec![Key::Z, Key::Backspace],
+                vec![Button::RightShoulder],
+                vec![],
+            ),
+            select: Btn::new(
+                vec![Key::Q],
+                vec![Button::LeftStick],
+                vec![],
+            ),
         }
     }
 
@@ -134,5 +183,41 @@ impl Input {
         self.up.update(controls);
         self.pause.update(controls);
         self.fullscreen.update(controls);
+        self.recipes.update(controls);
+        self.serve.update(controls);
+        self.hold.update(controls);
+        self.undo.update(controls);
+        self.select.update(controls);
+    }
+
+    /// the buttons pressed this frame, for recording a replay
+    pub fn frame(&self, frame_elapsed: f64) -> InputFrame {
+        InputFrame {
+            frame_elapsed,
+            left: self.left.down(true),
+            right: self.right.down(true),
+            down: self.down.down(true),
+            up: self.up.down(true),
+            pause: self.pause.down(true),
+            recipes: self.recipes.down(true),
+            serve: self.serve.down(true),
+            hold: self.hold.down(true),
+            undo: self.undo.down(true),
+            select: self.select.down(true),
+        }
+    }
+
+    /// use a recorded frame instead of the controls
+    pub fn set_frame(&mut self, frame: &InputFrame) {
+        self.left.set_pressed(frame.left);
+        self.right.set_pressed(frame.right);
+        self.down.set_pressed(frame.down);
+        self.up.set_pressed(frame.up);
+        self.pause.set_pressed(frame.pause);
+        self.recipes.set_pressed(frame.recipes);
+        self.serve.set_pressed(frame.serve);
+        self.hold.set_pressed(frame.hold);
+        self.undo.set_pressed(frame.undo);
+        self.select.set_pressed(frame.select);
     }
 }
@@ -0,0 +1,171 @@
+//! Scripted shifts, each defined by a file in `resources/data/levels`
+//! with the exact customers that arrive and how many must be served to win.
+
+use std::{fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    data::{load_json, DataError},
+    archetype::{Archetype, ArchetypeTable},
+    ingredient::{Ingredient, IngredientBook},
+    preset::Preset,
+    recipe::{Recipe, RecipeBook},
+    matching::{MatchRule, RuleFile},
+    generator::QueueGenerator,
+};
+
+/// A customer that arrives at a set time with a set order.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ScriptedCustomer {
+    /// seconds into the shift
+    pub time: f64,
+    /// seconds the customer waits before leaving angry
+    pub patience: f64,
+    /// from the bottom up, like the plate ingredients
+    pub order: Vec<Ingredient>,
+    /// the recipe ordered by name, `order` is its stack
+    pub recipe: Option<Recipe>,
+    pub rule: MatchRule,
+    pub archetype: Archetype,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Level {
+    /// the file name without the extension
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    /// lives, customer counts and queue size are taken from this preset
+    pub preset: String,
+    /// customers to serve to finish the level
+    pub goal: u64,
+    /// how the queue is filled instead of the preset's way
+    pub queue: Option<QueueGenerator>,
+    /// in order of arrival
+    pub customers: Vec<ScriptedCustomer>,
+}
+
+/// What level files refer to by id.
+pub struct LevelData<'a> {
+    pub ingredients: &'a IngredientBook,
+    pub recipes: &'a RecipeBook,
+    pub archetypes: &'a ArchetypeTable,
+    pub presets: &'a [Preset],
+}
+
+/// A level as it is written, with ingredients by id.
+#[derive(Deserialize)]
+struct LevelFile {
+    name: String,
+    description: String,
+    preset: String,
+    goal: u64,
+    #[serde(default)]
+    queue: Option<QueueGenerator>,
+    customers: Vec<CustomerFile>,
+}
+
+#[derive(Deserialize)]
+struct CustomerFile {
+    time: f64,
+    patience: f64,
+    /// either an order of ingredient ids or a recipe id
+    #[serde(default)]
+    order: Vec<String>,
+    #[serde(default)]
+    recipe: Option<String>,
+    /// the recipe's rule, or exact, if not given
+    #[serde(default)]
+    rule: Option<RuleFile>,
+    /// the base archetype if not given
+    #[serde(default)]
+    archetype: Option<String>,
+}
+
+impl Level {
+    /// parse the json of the level `id`
+    pub fn parse(id: &str, json: &str, data: &LevelData) -> Result<Level, String> {
+        let file: LevelFile = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        Self::from_file(id, file, data)
+    }
+
+    pub fn load(path: &Path, data: &LevelData) -> Result<Level, DataError> {
+        let id = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+        Self::from_file(&id, load_json(path)?, data)
+            .map_err(|e| DataError::Invalid(path.display().to_string(), e))
+    }
+
+    fn from_file(id: &str, file: LevelFile, data: &LevelData) -> Result<Level, String> {
+        let book = data.ingredients;
+        if !data.presets.iter().any(|p| p.id == file.preset) {
+            return Err(format!("unknown preset {}", file.preset));
+        }
+        if file.goal == 0 || file.goal as usize > file.customers.len() {
+            return Err("goal must be between 1 and the number of customers".to_string());
+        }
+        if let Some(queue) = &file.queue {
+            queue.validate(book)?;
+        }
+        let mut customers = Vec::new();
+        for (i, c) in file.customers.into_iter().enumerate() {
+            if c.patience <= 0.0 {
+                return Err(format!("customer {} must have some patience", i));
+            }
+            let recipe = match &c.recipe {
+                Some(id) => Some(data.recipes.find(id).ok_or_else(|| format!("unknown recipe {}", id))?),
+                None => None,
+            };
+            let mut order = Vec::new();
+            match recipe {
+                Some(recipe) if c.order.is_empty() => order = data.recipes.get(recipe).stack.clone(),
+                Some(_) => return Err(format!("customer {} has both an order and a recipe", i)),
+                None => for ing in c.order.iter() {
+                    order.push(book.find(ing).ok_or_else(|| format!("unknown ingredient {}", ing))?);
+                },
+            }
+            // plates always start with the base bun
+            if order.len() < 2 || order[0] != book.base_bun() {
+                return Err(format!("customer {} must order at least two ingredients, starting with {}",
+                                   i, book.get(book.base_bun()).id));
+            }
+            let rule = match (&c.rule, recipe) {
+                (Some(rule), _) => rule.resolve(book)?,
+                (None, Some(recipe)) => data.recipes.get(recipe).rule,
+                (None, None) => MatchRule::Exact,
+            };
+            rule.shape_order(&mut order);
+            let archetype = match &c.archetype {
+                Some(id) => data.archetypes.find(id).ok_or_else(|| format!("unknown archetype {}", id))?,
+                None => data.archetypes.base(),
+            };
+            customers.push(ScriptedCustomer { time: c.time, patience: c.patience, order, recipe, rule, archetype });
+        }
+        if customers.windows(2).any(|w| w[0].time > w[1].time) {
+            return Err("customers must be in order of arrival".to_string());
+        }
+        Ok(Level {
+            id: id.to_string(),
+            name: file.name,
+            description: file.description,
+            preset: file.preset,
+            goal: file.goal,
+            queue: file.queue,
+            customers,
+        })
+    }
+}
+
+/// every level in `dir`, in order of file name
+pub fn load_levels(dir: &Path, data: &LevelData) -> Result<Vec<Level>, DataError> {
+    let entries = fs::read_dir(dir).map_err(|e| DataError::Io(dir.display().to_string(), e))?;
+    let mut paths = Vec::new();
+    for entry in entries {
+        let path = entry.map_err(|e| DataError::Io(dir.display().to_string(), e))?.path();
+        if path.extension().is_some_and(|e| e == "json") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    paths.iter().map(|p| Level::load(p, data)).collect()
+}
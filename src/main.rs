@@ -1,3 +1,5 @@
+use std::{env, fs, path::Path, process};
+
 use nze_game_sdl::{
     DrawingArea,
     Render,
@@ -5,10 +7,39 @@ use nze_game_sdl::{
     geometry::{Rect, Vec2},
     Error,
 };
+use rand::prelude::*;
+
+use deli_cat_essen::{Game, VIEW_WIDTH, VIEW_HEIGHT, replay::Replay, sim::Simulation, data::DATA_DIR, rules::GameData, cli::arg_value};
 
-use deli_cat_essen::{Game, VIEW_WIDTH, VIEW_HEIGHT};
+/// where an unfinished game is kept between launches
+const SAVE_PATH: &str = "save.json";
 
 pub fn main() -> Result<(), Error> {
+    let args: Vec<String> = env::args().collect();
+    let replay = arg_value(&args, "--replay").map(|path| {
+        Replay::load(Path::new(&path)).unwrap_or_else(|e| {
+            eprintln!("failed to load replay {}: {}", path, e);
+            process::exit(1);
+        })
+    });
+    let seed = match (&replay, arg_value(&args, "--seed")) {
+        (Some(replay), _) => replay.seed,
+        (None, Some(seed)) => seed.parse().unwrap_or_else(|_| {
+            eprintln!("seed must be a positive integer, got {}", seed);
+            process::exit(1);
+        }),
+        (None, None) => thread_rng().gen(),
+    };
+    let data = GameData::load(Path::new(DATA_DIR)).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(1);
+    });
+    let mut recording = arg_value(&args, "--record").map(|path| (path, Replay::new(seed)));
+    let mut playback = replay.map(|r| r.frames.into_iter());
+    // resuming a save would make recordings impossible to replay
+    let use_save = recording.is_none() && playback.is_none();
+    let save_path = Path::new(SAVE_PATH);
+
     let (mut cam, drawing_area, context) = DrawingArea::new(
         "DSJ 2023",
         Rect::new(0.0, 0.0, VIEW_WIDTH, VIEW_HEIGHT),
@@ -16,18 +47,55 @@ pub fn main() -> Result<(), Error> {
     )?;
     let mut render = Render::new(drawing_area, &context)?;
     let mut controls = Controls::new(&context)?;
-    let mut game = Game::new(&mut render)?;
+    let mut game = Game::new(&mut render, seed, data)?;
+    if use_save && save_path.exists() {
+        match Simulation::load(save_path) {
+            Ok(sim) => game.resume(sim),
+            Err(e) => eprintln!("failed to load save {}: {}", SAVE_PATH, e),
+        }
+    }
 
     while !controls.should_close {
         controls.update(&cam);
-        game.update(&mut controls);
-        if game.input.fullscreen.down(true) {
+        // once the replay runs out the player takes over
+        let frame = match playback.as_mut().and_then(|frames| frames.next()) {
+            Some(frame) => {
+                game.play_frame(&frame);
+                frame
+            },
+            None => {
+                playback = None;
+                game.update(&mut controls);
+                game.input().frame(controls.frame_elapsed)
+            }
+        };
+        if let Some((_, r)) = recording.as_mut() {
+            r.record(frame);
+        }
+        if game.input().fullscreen.down(true) || game.take_fullscreen_request() {
             render.toggle_fullscreen(&mut cam)?;
         }
         render.start_draw();
         game.draw(&mut cam);
         render.end_draw(&mut cam)?;
     }
-    
+
+    if use_save {
+        match game.in_progress() {
+            Some(sim) => if let Err(e) = sim.save(save_path) {
+                eprintln!("failed to save game {}: {}", SAVE_PATH, e);
+            },
+            None => if save_path.exists() {
+                if let Err(e) = fs::remove_file(save_path) {
+                    eprintln!("failed to remove finished save {}: {}", SAVE_PATH, e);
+                }
+            },
+        }
+    }
+    if let Some((path, r)) = recording {
+        if let Err(e) = r.save(Path::new(&path)) {
+            eprintln!("failed to save replay {}: {}", path, e);
+        }
+    }
     Ok(())
 }
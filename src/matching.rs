@@ -0,0 +1,351 @@
+//! How a plate is checked against a customer's order.
+
+use std::collections::BTreeMap;
+
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    data::pick_weighted,
+    ingredient::{Ingredient, IngredientBook},
+};
+
+/// How much of the quality of a near miss comes from the buns, the rest is the fillings.
+const BUN_WEIGHT: f64 = 0.3;
+/// Taken off the fillings quality for each layer nobody asked for.
+const EXTRA_LAYER_PENALTY: f64 = 0.2;
+/// The best quality a plate that doesn't meet the order can have.
+const NEAR_MISS_MAX: f64 = 0.95;
+
+/// The names used for the rules in the data files.
+pub const RULE_NAMES: [&str; 5] = ["exact", "any_order", "at_least", "without", "any_fillings"];
+
+/// What counts as the order being met. Every rule needs the plate
+/// to have the same bottom and top buns as the order, the fillings
+/// are the ingredients between them.
+#[derive(Clone, Copy, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchRule {
+    /// every layer the same as the order
+    #[default]
+    Exact,
+    /// the same fillings as the order, stacked in any order
+    AnyOrder,
+    /// at least the fillings of the order, extras are fine
+    AtLeast,
+    /// any fillings as long as there are some and none are this ingredient
+    Without(Ingredient),
+    /// this many fillings of any kind
+    AnyFillings(usize),
+}
+
+/// A rule as it is written, with ingredients by id.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleFile {
+    #[default]
+    Exact,
+    AnyOrder,
+    AtLeast,
+    Without(String),
+    AnyFillings(usize),
+}
+
+impl RuleFile {
+    pub fn resolve(&self, book: &IngredientBook) -> Result<MatchRule, String> {
+        Ok(match self {
+            RuleFile::Exact => MatchRule::Exact,
+            RuleFile::AnyOrder => MatchRule::AnyOrder,
+            RuleFile::AtLeast => MatchRule::AtLeast,
+            RuleFile::Without(id) => MatchRule::Without(
+                book.find(id).ok_or_else(|| format!("unknown ingredient {}", id))?),
+            RuleFile::AnyFillings(count) => MatchRule::AnyFillings(*count),
+        })
+    }
+}
+
+impl MatchRule {
+    /// if `plate` meets `order` under this rule, both from the bottom up
+    pub fn matches(&self, order: &[Ingredient], plate: &[Ingredient]) -> bool {
+        if plate.len() < 2 || order.len() < 2
+            || plate[0] != order[0] || plate[plate.len() - 1] != order[order.len() - 1] {
+            return false;
+        }
+        let wanted = &order[1..order.len() - 1];
+        let fillings = &plate[1..plate.len() - 1];
+        match self {
+            MatchRule::Exact => order == plate,
+            MatchRule::AnyOrder => fillings.len() == wanted.len() && contains_all(fillings, wanted),
+            MatchRule::AtLeast => contains_all(fillings, wanted),
+            MatchRule::Without(ing) => !fillings.is_empty() && !fillings.contains(ing),
+            MatchRule::AnyFillings(count) => fillings.len() == *count,
+        }
+    }
+
+    /// how close `plate` is to meeting `order` under this rule, from 0 to 1,
+    /// only a plate that meets it is 1
+    pub fn quality(&self, order: &[Ingredient], plate: &[Ingredient]) -> f64 {
+        if self.matches(order, plate) {
+            return 1.0;
+        }
+        if plate.len() < 2 || order.len() < 2 {
+            return 0.0;
+        }
+        let mut buns = 0.0;
+        if plate[0] == order[0] {
+            buns += 0.5;
+        }
+        if plate[plate.len() - 1] == order[order.len() - 1] {
+            buns += 0.5;
+        }
+        let wanted = &order[1..order.len() - 1];
+        let fillings = &plate[1..plate.len() - 1];
+        let fill = match self {
+            MatchRule::Exact | MatchRule::AnyOrder | MatchRule::AtLeast => {
+                let found = overlap(fillings, wanted);
+                let layers = if wanted.is_empty() { 1.0 } else { found as f64 / wanted.len() as f64 };
+                // stacking order doesn't matter for the other rules, but the layers still have to be there
+                let in_place = match self {
+                    _ if wanted.is_empty() => 1.0,
+                    MatchRule::Exact =>
+                        wanted.iter().zip(fillings).filter(|(w, f)| w == f).count() as f64 / wanted.len() as f64,
+                    _ => layers,
+                };
+                let extra = match self {
+                    MatchRule::AtLeast => 0,
+                    _ => fillings.len() - found,
+                };
+                layers * 0.6 + in_place * 0.4 - extra as f64 * EXTRA_LAYER_PENALTY
+            },
+            MatchRule::Without(ing) if !fillings.is_empty() =>
+                fillings.iter().filter(|f| *f != ing).count() as f64 / fillings.len() as f64,
+            MatchRule::Without(_) => 0.0,
+            MatchRule::AnyFillings(count) =>
+                1.0 - (fillings.len() as f64 - *count as f64).abs() / (*count).max(1) as f64,
+        };
+        (buns * BUN_WEIGHT + fill.max(0.0) * (1.0 - BUN_WEIGHT)).clamp(0.0, NEAR_MISS_MAX)
+    }
+
+    /// the order a customer with this rule shows, only the buns
+    /// for rules that don't care which fillings are used
+    pub fn shape_order(&self, order: &mut Vec<Ingredient>) {
+        if let MatchRule::Without(_) | MatchRule::AnyFillings(_) = self {
+            if order.len() > 2 {
+                order.drain(1..order.len() - 1);
+            }
+        }
+    }
+
+    /// a short description for the speech bubble, none for exact orders
+    pub fn label(&self, book: &IngredientBook) -> Option<String> {
+        match self {
+            MatchRule::Exact => None,
+            MatchRule::AnyOrder => Some("Any Order".to_string()),
+            MatchRule::AtLeast => Some("Extras OK".to_string()),
+            MatchRule::Without(ing) => Some(format!("No {}", book.get(*ing).name)),
+            MatchRule::AnyFillings(count) => Some(format!("Any {} Fillings", count)),
+        }
+    }
+
+    /// a stack that meets `order` under this rule, for bots to aim for
+    pub fn example(&self, order: &[Ingredient], book: &IngredientBook) -> Vec<Ingredient> {
+        let filling = |not: Option<Ingredient>| book.iter()
+            .map(|(i, _)| i)
+            .find(|i| !book.is_bun(*i) && Some(*i) != not)
+            .unwrap_or(order[0]);
+        let mut stack = order.to_vec();
+        let count = match self {
+            MatchRule::Without(_) => 1,
+            MatchRule::AnyFillings(count) => *count,
+            _ => return stack,
+        };
+        let not = match self {
+            MatchRule::Without(ing) => Some(*ing),
+            _ => None,
+        };
+        let top = stack.pop().expect("orders have a top");
+        stack.truncate(1);
+        stack.extend(std::iter::repeat_n(filling(not), count));
+        stack.push(top);
+        stack
+    }
+
+    /// pick a rule with the weights in `table` by rule name, `order` is the random order the rule is for
+    pub fn random<R: Rng + ?Sized>(rng: &mut R, table: &BTreeMap<String, f64>,
+                                   order: &[Ingredient], book: &IngredientBook) -> MatchRule {
+        let weights: Vec<(usize, f64)> = RULE_NAMES.iter().enumerate()
+            .map(|(i, name)| (i, table.get(*name).copied().unwrap_or(0.0)))
+            .collect();
+        let fillings = order.len().saturating_sub(2);
+        match RULE_NAMES[pick_weighted(rng, &weights)] {
+            "any_order" => MatchRule::AnyOrder,
+            "at_least" => MatchRule::AtLeast,
+            "without" => {
+                let options: Vec<Ingredient> = book.iter().map(|(i, _)| i).filter(|i| !book.is_bun(*i)).collect();
+                // there has to be something left to fill the sandwitch with
+                if options.len() < 2 {
+                    return MatchRule::Exact;
+                }
+                MatchRule::Without(options[rng.gen_range(0..options.len())])
+            },
+            "any_fillings" if fillings > 0 => MatchRule::AnyFillings(fillings),
+            _ => MatchRule::Exact,
+        }
+    }
+}
+
+/// how many of `want` are in `have`, counting repeats
+fn overlap(have: &[Ingredient], want: &[Ingredient]) -> usize {
+    let mut left = have.to_vec();
+    want.iter().filter(|w| {
+        match left.iter().position(|h| h == *w) {
+            Some(i) => {
+                left.remove(i);
+                true
+            },
+            None => false,
+        }
+    }).count()
+}
+
+/// if `have` has at least as many of each ingredient as `want`
+fn contains_all(have: &[Ingredient], want: &[Ingredient]) -> bool {
+    want.iter().all(|w| {
+        have.iter().filter(|h| *h == w).count() >= want.iter().filter(|x| *x == w).count()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{customer::MIN_SERVE_QUALITY, rules::GameData};
+
+    const BUN: Ingredient = Ingredient(0);
+    const TOP: Ingredient = Ingredient(1);
+    const A: Ingredient = Ingredient(2);
+    const B: Ingredient = Ingredient(3);
+    const C: Ingredient = Ingredient(4);
+
+    const ORDER: [Ingredient; 4] = [BUN, A, B, TOP];
+
+    #[test]
+    fn exact_needs_every_layer_in_place() {
+        assert!(MatchRule::Exact.matches(&ORDER, &[BUN, A, B, TOP]));
+        assert!(!MatchRule::Exact.matches(&ORDER, &[BUN, B, A, TOP]));
+        assert!(!MatchRule::Exact.matches(&ORDER, &[BUN, A, TOP]));
+        assert!(!MatchRule::Exact.matches(&ORDER, &[BUN, A, B, C, TOP]));
+    }
+
+    #[test]
+    fn every_rule_needs_the_buns() {
+        for rule in [MatchRule::Exact, MatchRule::AnyOrder, MatchRule::AtLeast,
+                     MatchRule::Without(C), MatchRule::AnyFillings(2)] {
+            assert!(!rule.matches(&ORDER, &[A, A, B, TOP]), "{:?}", rule);
+            assert!(!rule.matches(&ORDER, &[BUN, A, B, A]), "{:?}", rule);
+            assert!(!rule.matches(&ORDER, &[BUN]), "{:?}", rule);
+        }
+    }
+
+    #[test]
+    fn any_order_takes_the_same_fillings_stacked_any_way() {
+        assert!(MatchRule::AnyOrder.matches(&ORDER, &[BUN, A, B, TOP]));
+        assert!(MatchRule::AnyOrder.matches(&ORDER, &[BUN, B, A, TOP]));
+        assert!(!MatchRule::AnyOrder.matches(&ORDER, &[BUN, A, TOP]));
+        assert!(!MatchRule::AnyOrder.matches(&ORDER, &[BUN, B, A, C, TOP]));
+        assert!(!MatchRule::AnyOrder.matches(&ORDER, &[BUN, A, A, TOP]));
+    }
+
+    #[test]
+    fn at_least_allows_extras() {
+        assert!(MatchRule::AtLeast.matches(&ORDER, &[BUN, B, A, TOP]));
+        assert!(MatchRule::AtLeast.matches(&ORDER, &[BUN, C, B, A, A, TOP]));
+        assert!(!MatchRule::AtLeast.matches(&ORDER, &[BUN, A, C, TOP]));
+        assert!(!MatchRule::AtLeast.matches(&[BUN, A, A, TOP], &[BUN, A, C, TOP]));
+    }
+
+    #[test]
+    fn without_takes_any_fillings_but_one() {
+        let rule = MatchRule::Without(C);
+        assert!(rule.matches(&[BUN, TOP], &[BUN, A, TOP]));
+        assert!(rule.matches(&[BUN, TOP], &[BUN, B, A, B, TOP]));
+        assert!(!rule.matches(&[BUN, TOP], &[BUN, A, C, TOP]));
+        assert!(!rule.matches(&[BUN, TOP], &[BUN, TOP]));
+    }
+
+    #[test]
+    fn any_fillings_counts_the_layers() {
+        let rule = MatchRule::AnyFillings(2);
+        assert!(rule.matches(&[BUN, TOP], &[BUN, C, C, TOP]));
+        assert!(rule.matches(&[BUN, TOP], &[BUN, A, B, TOP]));
+        assert!(!rule.matches(&[BUN, TOP], &[BUN, A, TOP]));
+        assert!(!rule.matches(&[BUN, TOP], &[BUN, A, B, C, TOP]));
+    }
+
+    #[test]
+    fn quality_is_one_only_for_a_match() {
+        assert_eq!(MatchRule::AnyOrder.quality(&ORDER, &[BUN, B, A, TOP]), 1.0);
+        assert_eq!(MatchRule::AtLeast.quality(&ORDER, &[BUN, A, C, B, TOP]), 1.0);
+        for plate in [[BUN, A, C, TOP], [BUN, A, B, A], [BUN, C, C, TOP]] {
+            assert!(MatchRule::AnyOrder.quality(&ORDER, &plate) <= NEAR_MISS_MAX);
+            assert!(MatchRule::AtLeast.quality(&ORDER, &plate) <= NEAR_MISS_MAX);
+        }
+    }
+
+    #[test]
+    fn swapped_fillings_only_cost_exact_orders() {
+        let swapped = [BUN, B, A, TOP];
+        let exact = MatchRule::Exact.quality(&ORDER, &swapped);
+        assert!(exact < NEAR_MISS_MAX);
+        assert!(exact >= MIN_SERVE_QUALITY);
+        assert_eq!(MatchRule::AnyOrder.quality(&ORDER, &swapped), 1.0);
+    }
+
+    #[test]
+    fn missing_fillings_lower_the_quality() {
+        for rule in [MatchRule::Exact, MatchRule::AnyOrder, MatchRule::AtLeast] {
+            let one = rule.quality(&ORDER, &[BUN, A, TOP]);
+            let none = rule.quality(&ORDER, &[BUN, TOP]);
+            assert!(one < NEAR_MISS_MAX, "{:?}", rule);
+            assert!(none < one, "{:?}", rule);
+        }
+    }
+
+    #[test]
+    fn extra_fillings_cost_all_but_at_least() {
+        let extra = [BUN, A, B, C, TOP];
+        assert!(MatchRule::Exact.quality(&ORDER, &extra) < NEAR_MISS_MAX);
+        assert!(MatchRule::AnyOrder.quality(&ORDER, &extra) < NEAR_MISS_MAX);
+        assert!(MatchRule::AnyOrder.quality(&ORDER, &extra) > MatchRule::AnyOrder.quality(&ORDER, &[BUN, A, C, C, TOP]));
+        assert_eq!(MatchRule::AtLeast.quality(&ORDER, &extra), 1.0);
+    }
+
+    #[test]
+    fn bare_bread_is_never_good_enough() {
+        for rule in [MatchRule::Exact, MatchRule::AnyOrder, MatchRule::AtLeast,
+                     MatchRule::Without(C), MatchRule::AnyFillings(2)] {
+            assert!(rule.quality(&ORDER, &[BUN, TOP]) < MIN_SERVE_QUALITY, "{:?}", rule);
+        }
+    }
+
+    #[test]
+    fn labels_use_the_ingredient_names() {
+        let book = GameData::builtin().ingredients;
+        let tomato = book.find("tomato").unwrap();
+        assert_eq!(MatchRule::Without(tomato).label(&book).unwrap(), "No Tomato");
+        assert_eq!(MatchRule::Exact.label(&book), None);
+    }
+
+    #[test]
+    fn examples_meet_their_rule() {
+        let book = GameData::builtin().ingredients;
+        let bun = book.base_bun();
+        let fillings: Vec<Ingredient> = book.iter().map(|(i, _)| i).filter(|i| !book.is_bun(*i)).collect();
+        let order = [bun, fillings[0], fillings[1], bun];
+        for rule in [MatchRule::Exact, MatchRule::AnyOrder, MatchRule::AtLeast,
+                     MatchRule::Without(fillings[0]), MatchRule::AnyFillings(3)] {
+            let mut shown = order.to_vec();
+            rule.shape_order(&mut shown);
+            assert!(rule.matches(&shown, &rule.example(&shown, &book)), "{:?}", rule);
+        }
+    }
+}
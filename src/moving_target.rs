@@ -1,9 +1,11 @@
-use nze_game_sdl::geometry::Vec2;
+use serde::{Deserialize, Serialize};
 
+use crate::geometry::Vec2;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub struct Target {
     current_pos: Vec2,
+    prev_pos: Vec2,
     target_pos: Vec2,
     pub speed: f64,
     pub breath: bool,
@@ -11,9 +13,16 @@ pub struct Target {
     pub breath_speed: f64,
     time: f64,
     offset: Vec2,
+    prev_offset: Vec2,
     perm_off: Vec2,
 }
 
+impl Default for Target {
+    fn default() -> Target {
+        Target::new()
+    }
+}
+
 impl Target {
     pub fn new() -> Target {
         Self::new_with_speed(100.0, Vec2::zero(), Vec2::zero())
@@ -22,11 +31,13 @@ impl Target {
     pub fn new_with_speed(speed: f64, target: Vec2, perm_off: Vec2) -> Target {
         Target {
             current_pos: target,
+            prev_pos: target,
             target_pos: target,
             speed,
             breath: false,
             breath_size: Vec2::new(1.0, 1.5),
             offset: Vec2::zero(),
+            prev_offset: Vec2::zero(),
             time: 0.0,
             breath_speed: 2.0,
             perm_off,
@@ -37,6 +48,7 @@ impl Target {
         self.target_pos = target;
         if self.current_pos == Vec2::zero() {
             self.current_pos = self.target_pos;
+            self.prev_pos = self.current_pos;
         }
     }
 
@@ -45,6 +57,7 @@ impl Target {
     }
 
     pub fn update(&mut self, dt: f64) {
+        self.prev_pos = self.current_pos;
         if  self.current_pos == self.target_pos {
             return;
         }
@@ -58,6 +71,7 @@ impl Target {
     }
 
     pub fn breath_update(&mut self, dt: f64) {
+        self.prev_offset = self.offset;
         if self.breath {
             self.time += dt;
             self.offset = self.breath_size * (self.time * self.breath_speed).sin();
@@ -71,4 +85,17 @@ impl Target {
     pub fn get_pos_no_offset(&self) -> Vec2 {
         self.current_pos + self.perm_off
     }
+
+    /// position between the last update and this one, `alpha` from 0 to 1
+    pub fn get_draw_pos(&self, alpha: f64) -> Vec2 {
+        self.get_draw_pos_no_offset(alpha) + lerp(self.prev_offset, self.offset, alpha)
+    }
+
+    pub fn get_draw_pos_no_offset(&self, alpha: f64) -> Vec2 {
+        lerp(self.prev_pos, self.current_pos, alpha) + self.perm_off
+    }
+}
+
+fn lerp(a: Vec2, b: Vec2, t: f64) -> Vec2 {
+    a + (b - a) * t
 }
@@ -0,0 +1,77 @@
+//! Difficulty presets, defined in `resources/data/presets.json`
+//! and picked from a menu before a shift starts.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{difficulty::Difficulty, matching::RULE_NAMES, generator::QueueGenerator};
+
+/// The preset used when none is chosen.
+pub const DEFAULT_PRESET: &str = "normal";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Preset {
+    pub id: String,
+    pub name: String,
+    /// shown under the name in the menu
+    pub description: String,
+    pub lives: u32,
+    /// customers at the counter with an order
+    pub active_customers: usize,
+    /// customers in line, including those without an order yet
+    pub max_customers: usize,
+    /// ingredients waiting to be released
+    pub queue_size: usize,
+    /// how the queue is filled, by the ingredient weights if not given
+    #[serde(default)]
+    pub queue: QueueGenerator,
+    /// chance of a random order being one from the recipe book
+    #[serde(default)]
+    pub recipe_chance: f64,
+    pub difficulty: Difficulty,
+    /// archetype ids and how likely each is to turn up,
+    /// when empty the weights in `archetypes.json` are used
+    #[serde(default)]
+    pub spawn_table: BTreeMap<String, f64>,
+    /// match rule names and how likely a random order is to use each,
+    /// when empty every order is exact
+    #[serde(default)]
+    pub match_rules: BTreeMap<String, f64>,
+    /// seconds of a day in endless mode, 0 plays one long day
+    #[serde(default)]
+    pub day_length: f64,
+    /// how many customers served further along the difficulty curves each day starts
+    #[serde(default)]
+    pub day_step: f64,
+    /// points taken from the score for each undo
+    #[serde(default)]
+    pub undo_cost: u64,
+}
+
+impl Preset {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.lives == 0 {
+            return Err(format!("preset {} must have at least one life", self.id));
+        }
+        if self.active_customers == 0 || self.max_customers < self.active_customers {
+            return Err(format!(
+                "preset {} must have at least one active customer and no more than max_customers",
+                self.id));
+        }
+        if !(0.0..=1.0).contains(&self.recipe_chance) {
+            return Err(format!("preset {} recipe_chance must be between 0 and 1", self.id));
+        }
+        if let Some(name) = self.match_rules.keys().find(|n| !RULE_NAMES.contains(&n.as_str())) {
+            return Err(format!("preset {} has unknown match rule {}, try one of {}",
+                               self.id, name, RULE_NAMES.join(", ")));
+        }
+        if self.day_length < 0.0 || self.day_step < 0.0 {
+            return Err(format!("preset {} day_length and day_step can't be negative", self.id));
+        }
+        if self.queue_size == 0 {
+            return Err(format!("preset {} must have a queue", self.id));
+        }
+        self.difficulty.validate().map_err(|e| format!("preset {}: {}", self.id, e))
+    }
+}
@@ -0,0 +1,109 @@
+//! Named sandwitches customers can order, defined in `resources/data/recipes.json`.
+
+use std::path::Path;
+
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    data::{load_json, pick_weighted, DataError},
+    ingredient::{Ingredient, IngredientBook},
+    matching::{MatchRule, RuleFile},
+};
+
+/// A recipe, the index of its definition in the `RecipeBook`.
+#[derive(Hash, Eq, PartialEq, Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Recipe(pub usize);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecipeDef {
+    pub id: String,
+    pub name: String,
+    /// shown in the speech bubble, the name is shown if there is none
+    pub icon: Option<String>,
+    /// how likely the recipe is to be ordered compared to the others
+    pub weight: f64,
+    /// from the bottom up, like the plate ingredients
+    pub stack: Vec<Ingredient>,
+    /// how the stack is checked, exact if not given
+    #[serde(default)]
+    pub rule: MatchRule,
+}
+
+/// A recipe as it is written, with ingredients by id.
+#[derive(Deserialize)]
+struct RecipeFile {
+    id: String,
+    name: String,
+    #[serde(default)]
+    icon: Option<String>,
+    weight: f64,
+    stack: Vec<String>,
+    #[serde(default)]
+    rule: RuleFile,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct RecipeBook {
+    defs: Vec<RecipeDef>,
+}
+
+impl RecipeBook {
+    /// parse the json of a recipe file
+    pub fn parse(json: &str, book: &IngredientBook) -> Result<RecipeBook, String> {
+        Self::from_files(serde_json::from_str(json).map_err(|e| e.to_string())?, book)
+    }
+
+    pub fn load(path: &Path, book: &IngredientBook) -> Result<RecipeBook, DataError> {
+        Self::from_files(load_json(path)?, book)
+            .map_err(|e| DataError::Invalid(path.display().to_string(), e))
+    }
+
+    fn from_files(files: Vec<RecipeFile>, book: &IngredientBook) -> Result<RecipeBook, String> {
+        let mut defs: Vec<RecipeDef> = Vec::new();
+        for f in files {
+            if defs.iter().any(|d| d.id == f.id) {
+                return Err(format!("recipe {} is defined twice", f.id));
+            }
+            if f.weight <= 0.0 {
+                return Err(format!("recipe {} must have a weight above zero", f.id));
+            }
+            let mut stack = Vec::new();
+            for ing in f.stack.iter() {
+                stack.push(book.find(ing).ok_or_else(|| format!("unknown ingredient {} in {}", ing, f.id))?);
+            }
+            // plates always start with the base bun
+            if stack.len() < 2 || stack[0] != book.base_bun() {
+                return Err(format!("recipe {} must have at least two ingredients, starting with {}",
+                                   f.id, book.get(book.base_bun()).id));
+            }
+            let rule = f.rule.resolve(book).map_err(|e| format!("{} in {}", e, f.id))?;
+            defs.push(RecipeDef { id: f.id, name: f.name, icon: f.icon, weight: f.weight, stack, rule });
+        }
+        Ok(RecipeBook { defs })
+    }
+
+    pub fn get(&self, recipe: Recipe) -> &RecipeDef {
+        &self.defs[recipe.0]
+    }
+
+    pub fn find(&self, id: &str) -> Option<Recipe> {
+        self.defs.iter().position(|d| d.id == id).map(Recipe)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Recipe, &RecipeDef)> {
+        self.defs.iter().enumerate().map(|(i, d)| (Recipe(i), d))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.defs.is_empty()
+    }
+
+    /// pick a recipe using the weights
+    pub fn random<R: Rng + ?Sized>(&self, rng: &mut R) -> Recipe {
+        let weights: Vec<(usize, f64)> = self.defs.iter().enumerate().map(|(i, d)| (i, d.weight)).collect();
+        Recipe(pick_weighted(rng, &weights))
+    }
+}
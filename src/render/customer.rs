@@ -0,0 +1,163 @@
+use std::path::Path;
+
+use nze_game_sdl::{Camera, geometry::{Vec2, Rect}, GameObject, Render, Error, Colour, resource::Font};
+
+use crate::{
+    customer::{Customer, CUSTOMER_OFFSET},
+    archetype::ArchetypeTable,
+    recipe::RecipeBook,
+    sim::Simulation,
+};
+use super::SandwitchRender;
+
+pub struct CustomerRender {
+    /// indexed by `Archetype`
+    customers: Vec<GameObject>,
+    /// indexed by `Recipe`, none if the recipe has no icon
+    recipe_icons: Vec<Option<GameObject>>,
+    speech: GameObject,
+}
+const CUSTOMER_ING_SIZE: Vec2 = Vec2::new(24.0, 12.0);
+const CUSTOMER_ING_OFFSET: Vec2 = Vec2::new(27.0, 30.0);
+const CUSTOMER_ING_SPACING: f64 = -CUSTOMER_ING_SIZE.y * 0.5;
+
+const CUSTOMER_SPEECH_OFFSET: Vec2 = Vec2::new(2.0, 25.0);
+
+const RECIPE_NAME_SIZE: u32 = 10;
+const RECIPE_NAME_HEIGHT: f64 = 20.0;
+const RULE_LABEL_SIZE: u32 = 9;
+const REACTION_SIZE: u32 = 12;
+const REACTION_OFFSET: Vec2 = Vec2::new(5.0, -20.0);
+const RULE_LABEL_HEIGHT: f64 = 12.0;
+
+const CUSTOMER_PATIENCE_OFFSET: Rect = Rect::new(15.0, -5.0, 30.0, 5.0);
+const SELECTED_BORDER: f64 = 2.0;
+
+impl CustomerRender {
+    pub fn new(render: &mut Render, archetypes: &ArchetypeTable, recipes: &RecipeBook) -> Result<CustomerRender, Error> {
+        let mut customers = Vec::new();
+        for (_, def) in archetypes.iter() {
+            customers.push(GameObject::new_from_tex(render.texture_manager.load(Path::new(&def.sprite))?));
+        }
+        let mut recipe_icons = Vec::new();
+        for (_, def) in recipes.iter() {
+            recipe_icons.push(match &def.icon {
+                Some(icon) => Some(GameObject::new_from_tex(render.texture_manager.load(Path::new(icon))?)),
+                None => None,
+            });
+        }
+        Ok(CustomerRender {
+            customers,
+            recipe_icons,
+            speech: GameObject::new_from_tex(
+                render.texture_manager.load(
+                    Path::new("resources/textures/speech.png"))?
+            ),
+        })
+    }
+    
+    /// the customers of `sim`, with the one plates go to outlined when serving manually
+    pub fn draw(&self, cam: &mut Camera, sim: &Simulation, sw_render: &SandwitchRender,
+                font: &Font, alpha: f64) {
+        let customers = sim.customer_line();
+        let rules = sim.rules();
+        let selected = sim.manual_serving().then(|| customers.selected());
+        for (i, c) in customers.customers.iter().enumerate() {
+            self.draw_customer(cam, c, alpha);
+            if c.waiting {
+                self.draw_patience(cam, c, selected == Some(i), alpha);
+            }
+        }
+        for i in 0..customers.active_customers {
+            if customers.customers.len() <= i { break; }
+            if !customers.customers[i].waiting {
+                continue;
+            }
+            let c = &customers.customers[i];
+            let pos_abs = c.target.get_draw_pos_no_offset(alpha);
+            let base = Vec2::new(pos_abs.x - CUSTOMER_OFFSET.x,
+                                 pos_abs.y - CUSTOMER_OFFSET.y + CUSTOMER_ING_OFFSET.y);
+            let icon = c.recipe.and_then(|r| self.recipe_icons[r.0].as_ref());
+            let label = c.rule.label(&rules.ingredients);
+            let mut speech = self.speech.clone();
+            speech.rect.x = pos_abs.x - CUSTOMER_OFFSET.x + CUSTOMER_ING_OFFSET.x + CUSTOMER_SPEECH_OFFSET.x;
+            speech.rect.h = match (c.recipe, icon) {
+                (Some(_), None) => RECIPE_NAME_HEIGHT,
+                _ => c.ings.len() as f64 * (CUSTOMER_ING_SIZE.y + CUSTOMER_ING_SPACING),
+            } + 25.0 + if label.is_some() { RULE_LABEL_HEIGHT } else { 0.0 };
+            speech.rect.y = (pos_abs.y - CUSTOMER_OFFSET.y - speech.rect.h) + CUSTOMER_SPEECH_OFFSET.y + CUSTOMER_ING_OFFSET.y;
+            cam.draw(&speech);
+            if let Some(label) = label {
+                cam.draw_disposable_text(font, label, RULE_LABEL_SIZE,
+                                         Vec2::new(speech.rect.x + 6.0, speech.rect.y + 5.0),
+                                         Colour::new(0, 0, 0, 255), Vec2::new(1.0, 1.0));
+            }
+            match (c.recipe, icon) {
+                (Some(_), Some(icon)) => {
+                    // scaled to the width of the ingredients, sitting where the bottom one would
+                    let mut icon = icon.clone();
+                    icon.rect.h *= CUSTOMER_ING_SIZE.x / icon.rect.w;
+                    icon.rect.w = CUSTOMER_ING_SIZE.x;
+                    icon.rect.x = base.x + CUSTOMER_ING_OFFSET.x + 13.0;
+                    icon.rect.y = base.y + CUSTOMER_ING_SIZE.y - icon.rect.h;
+                    cam.draw(&icon);
+                },
+                (Some(recipe), None) => {
+                    cam.draw_disposable_text(font, rules.recipes.get(recipe).name.clone(), RECIPE_NAME_SIZE,
+                                             Vec2::new(speech.rect.x + 6.0, base.y - RECIPE_NAME_HEIGHT + 6.0),
+                                             Colour::new(0, 0, 0, 255), Vec2::new(1.0, 1.0));
+                },
+                (None, _) => sw_render.render_ings(cam, c.ings.iter(),
+                                                   Vec2::new(base.x + CUSTOMER_ING_OFFSET.x + 13.0, base.y),
+                                                   CUSTOMER_ING_SIZE, CUSTOMER_ING_SPACING, -1.0),
+            }
+        }
+        for c in customers.leaving_customers.iter() {
+            self.draw_customer(cam, c, alpha);
+            self.draw_reaction(cam, c, font, alpha);
+            sw_render.render_sw(cam, c.sandwitch.as_ref().unwrap(), alpha)
+        }
+        for c in customers.angry_customers.iter() {
+            self.draw_customer(cam, c, alpha);
+        }
+    }
+
+    fn draw_customer(&self, cam: &mut Camera, c: &Customer, alpha: f64) {
+        let mut go = self.customers[c.archetype.0].clone();
+        let pos = c.target.get_draw_pos(alpha);
+        go.rect.x = pos.x;
+        go.rect.y = pos.y;
+        cam.draw(&go);
+    }
+
+    fn draw_reaction(&self, cam: &mut Camera, c: &Customer, font: &Font, alpha: f64) {
+        let (text, colour) = if c.quality >= 1.0 {
+            ("Purrfect!", Colour::new(124, 199, 109, 255))
+        } else {
+            ("Sloppy...", Colour::new(214, 140, 69, 255))
+        };
+        let pos = c.target.get_draw_pos(alpha);
+        cam.draw_disposable_text(font, text.to_string(), REACTION_SIZE,
+                                 Vec2::new(pos.x + REACTION_OFFSET.x, pos.y + REACTION_OFFSET.y),
+                                 colour, Vec2::new(1.0, 1.0));
+    }
+
+    /// a selected customer's bar is outlined
+    fn draw_patience(&self, cam: &mut Camera, c: &Customer, selected: bool, alpha: f64) {
+        let mut pos = c.target.get_draw_pos(alpha);
+        pos.x += CUSTOMER_PATIENCE_OFFSET.x;
+        pos.y += CUSTOMER_PATIENCE_OFFSET.y;
+        if selected {
+            cam.draw_rect(Rect::new(pos.x - SELECTED_BORDER, pos.y - SELECTED_BORDER,
+                                    CUSTOMER_PATIENCE_OFFSET.w + SELECTED_BORDER * 2.0,
+                                    CUSTOMER_PATIENCE_OFFSET.h + SELECTED_BORDER * 2.0),
+                          Colour::new(255, 220, 120, 255), Vec2::new(1.0, 1.0));
+        }
+        let ratio = c.wait_time / c.wait_max;
+        let length = CUSTOMER_PATIENCE_OFFSET.w * ratio;
+        cam.draw_rect(Rect::new(pos.x, pos.y, CUSTOMER_PATIENCE_OFFSET.w, CUSTOMER_PATIENCE_OFFSET.h),
+                      Colour::new(124, 199, 109, 255), Vec2::new(1.0, 1.0));
+        cam.draw_rect(Rect::new(pos.x, pos.y, length, CUSTOMER_PATIENCE_OFFSET.h),
+                      Colour::new(117, 68, 68, 255), Vec2::new(1.0, 1.0));
+    }
+}
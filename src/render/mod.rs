@@ -0,0 +1,5 @@
+mod sandwitch;
+mod customer;
+
+pub use sandwitch::SandwitchRender;
+pub use customer::CustomerRender;
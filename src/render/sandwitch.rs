@@ -0,0 +1,98 @@
+use std::path::Path;
+
+use nze_game_sdl::{Render, Camera, Error, GameObject, geometry::Vec2};
+
+use crate::{
+    sandwitch::{Sandwitch, SandwitchMachine, ING_SIZE},
+    ingredient::{Ingredient, IngredientBook},
+};
+
+pub struct SandwitchRender {
+    /// indexed by `Ingredient`
+    ingredient: Vec<GameObject>,
+    chef: GameObject,
+    restauraunt_front: GameObject,
+    delicat: GameObject,
+    plate: GameObject,
+}
+
+const CHEF_OFFSET: Vec2 = Vec2::new(-20.0, 100.0);
+const PLATE_OFFSET: Vec2 = Vec2::new(-2.0, 11.0);
+
+impl SandwitchRender {
+    pub fn new(render: &mut Render, book: &IngredientBook) -> Result<SandwitchRender, Error> {
+        Ok(SandwitchRender {
+            ingredient: Self::load_ingredients(render, book)?,
+            chef: GameObject::new_from_tex(render.texture_manager.load(Path::new("resources/textures/chef.png"))?),
+            delicat: GameObject::new_from_tex(render.texture_manager.load(Path::new("resources/textures/deli-cat.png"))?),
+            restauraunt_front: GameObject::new_from_tex(render.texture_manager.load(
+                Path::new("resources/textures/restaurant_front.png"))?),
+            plate: GameObject::new_from_tex(render.texture_manager.load(
+                Path::new("resources/textures/plate.png"))?),
+        })
+    }
+
+    pub fn draw(&mut self, cam: &mut Camera, machine: &SandwitchMachine, alpha: f64) {
+        let chef_pos = machine.queue.target.get_draw_pos(alpha);
+        self.chef.rect.x = chef_pos.x + CHEF_OFFSET.x;
+        self.chef.rect.y = chef_pos.y + CHEF_OFFSET.y;
+        cam.draw(&self.chef);
+        cam.draw(&self.restauraunt_front);
+        let delicat_pos = machine.delicat_target.get_draw_pos(alpha);
+        self.delicat.rect.x = delicat_pos.x;
+        self.delicat.rect.y = delicat_pos.y;
+        cam.draw(&self.delicat);
+        self.render_sw(cam, &machine.queue, alpha);
+        let hold_pos = machine.hold.target.get_draw_pos(alpha);
+        let mut slot = self.plate.clone();
+        slot.rect.x = hold_pos.x + PLATE_OFFSET.x;
+        slot.rect.y = hold_pos.y + PLATE_OFFSET.y;
+        cam.draw(&slot);
+        self.render_sw(cam, &machine.hold, alpha);
+
+        for (i, sw) in machine.sandwitches.iter().enumerate() {
+            if i != 0 {
+                let mut p = self.plate.clone();
+                let pos = sw.target.get_draw_pos(alpha);
+                p.rect.x = pos.x + PLATE_OFFSET.x;
+                p.rect.y = pos.y + PLATE_OFFSET.y;
+                cam.draw(&p);
+            }
+            self.render_sw(cam, sw, alpha);
+        }
+    }
+
+    pub fn render_sw(&self, cam: &mut Camera, sw: &Sandwitch, alpha: f64) {
+        for (i, ing) in sw.ingredients.iter().enumerate() {
+            let mut ing = self.ingredient[ing.0].clone();
+            ing.rect.w = ING_SIZE.x;
+            ing.rect.h = ING_SIZE.y;
+            let pos = sw.ing_targets[i].get_draw_pos_no_offset(alpha);
+            ing.rect.x = pos.x;
+            ing.rect.y = pos.y;
+            cam.draw(&ing);
+        }
+    }
+
+    pub fn render_ings<'a>(&self, cam: &mut Camera, ings: impl Iterator<Item = &'a Ingredient>,
+                            base: Vec2, ing_size: Vec2, ing_spacing: f64, dir_mod: f64
+    ) {
+        for (i, ing) in ings.enumerate() {
+            let mut ing = self.ingredient[ing.0].clone();
+            ing.rect.w = ing_size.x;
+            ing.rect.h = ing_size.y;
+            ing.rect.x = base.x;
+            ing.rect.y = base.y + dir_mod * (i as f64 * (ing_size.y + ing_spacing));
+            cam.draw(&ing);
+        }
+    }
+
+    /// the texture of each ingredient in `book`, in the same order
+    fn load_ingredients(render: &mut Render, book: &IngredientBook) -> Result<Vec<GameObject>, Error> {
+        let mut textures = Vec::new();
+        for (_, def) in book.iter() {
+            textures.push(GameObject::new_from_tex(render.texture_manager.load(Path::new(&def.texture))?));
+        }
+        Ok(textures)
+    }
+}
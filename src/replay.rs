@@ -0,0 +1,70 @@
+//! Recording of the player's input, which together with the game seed
+//! reproduces a session exactly.
+
+use std::{fs::File, io::{self, BufReader, BufWriter}, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::sim::Action;
+
+/// The buttons pressed during one frame and how long the frame took.
+#[derive(Clone, Copy, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct InputFrame {
+    pub frame_elapsed: f64,
+    pub left: bool,
+    pub right: bool,
+    pub down: bool,
+    pub up: bool,
+    pub pause: bool,
+    #[serde(default)]
+    pub recipes: bool,
+    #[serde(default)]
+    pub serve: bool,
+    #[serde(default)]
+    pub hold: bool,
+    #[serde(default)]
+    pub undo: bool,
+    #[serde(default)]
+    pub select: bool,
+}
+
+impl InputFrame {
+    /// what the pressed buttons do to the game, pause and the recipe book open menus instead
+    pub fn actions(&self) -> Vec<Action> {
+        [
+            (self.left, Action::Switch(-1)),
+            (self.right, Action::Switch(1)),
+            (self.down, Action::Release),
+            (self.up, Action::Bin),
+            (self.serve, Action::Serve),
+            (self.hold, Action::Hold),
+            (self.undo, Action::Undo),
+            (self.select, Action::SelectCustomer(1)),
+        ].into_iter().filter(|(pressed, _)| *pressed).map(|(_, action)| action).collect()
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Replay {
+    pub seed: u64,
+    pub frames: Vec<InputFrame>,
+}
+
+impl Replay {
+    pub fn new(seed: u64) -> Replay {
+        Replay { seed, frames: Vec::new() }
+    }
+
+    pub fn record(&mut self, frame: InputFrame) {
+        self.frames.push(frame);
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), io::Error> {
+        serde_json::to_writer(BufWriter::new(File::create(path)?), self)?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Replay, io::Error> {
+        Ok(serde_json::from_reader(BufReader::new(File::open(path)?))?)
+    }
+}
@@ -0,0 +1,145 @@
+//! Everything that decides how a game plays, most of it from the data files.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    archetype::ArchetypeTable,
+    data::{load_json, parse_builtin, DataError},
+    difficulty::Difficulty,
+    ingredient::IngredientBook,
+    level::{Level, LevelData, load_levels},
+    preset::{Preset, DEFAULT_PRESET},
+    recipe::RecipeBook,
+    upgrade::UpgradeShop,
+    generator::QueueGenerator,
+};
+
+/// The levels shipped with the game, built into the binary.
+const BUILTIN_LEVELS: [(&str, &str); 3] = [
+    ("01_opening_day", include_str!("../resources/data/levels/01_opening_day.json")),
+    ("02_lunch_rush", include_str!("../resources/data/levels/02_lunch_rush.json")),
+    ("03_food_critic", include_str!("../resources/data/levels/03_food_critic.json")),
+];
+
+/// The rules of a single game.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Rules {
+    pub ingredients: IngredientBook,
+    pub recipes: RecipeBook,
+    pub archetypes: ArchetypeTable,
+    pub preset: Preset,
+    /// the scripted shift being played, none for endless mode
+    pub level: Option<Level>,
+    /// what can be bought between days
+    pub upgrades: UpgradeShop,
+}
+
+impl Rules {
+    /// the default preset of the data files built into the binary
+    pub fn builtin() -> Rules {
+        GameData::builtin().default_rules()
+    }
+
+    /// how the queue is filled, the level's way if it has one
+    pub fn queue_generator(&self) -> &QueueGenerator {
+        self.level.as_ref().and_then(|l| l.queue.as_ref()).unwrap_or(&self.preset.queue)
+    }
+
+    pub fn load_difficulty(path: &Path) -> Result<Difficulty, DataError> {
+        let difficulty: Difficulty = load_json(path)?;
+        difficulty.validate().map_err(|e| DataError::Invalid(path.display().to_string(), e))?;
+        Ok(difficulty)
+    }
+}
+
+/// Everything loaded from the data files, games are played with `Rules` made from it.
+#[derive(Clone, Debug)]
+pub struct GameData {
+    pub ingredients: IngredientBook,
+    pub recipes: RecipeBook,
+    pub archetypes: ArchetypeTable,
+    pub presets: Vec<Preset>,
+    pub levels: Vec<Level>,
+    pub upgrades: UpgradeShop,
+}
+
+impl GameData {
+    /// the data files shipped with the game, built into the binary
+    pub fn builtin() -> GameData {
+        let ingredients = IngredientBook::new(parse_builtin(
+            "ingredients.json", include_str!("../resources/data/ingredients.json")))
+            .expect("built in ingredients are valid");
+        let recipes = RecipeBook::parse(include_str!("../resources/data/recipes.json"), &ingredients)
+            .unwrap_or_else(|e| panic!("built in recipes are invalid: {}", e));
+        let archetypes = ArchetypeTable::new(parse_builtin(
+            "archetypes.json", include_str!("../resources/data/archetypes.json")))
+            .expect("built in archetypes are valid");
+        let presets: Vec<Preset> = parse_builtin("presets.json", include_str!("../resources/data/presets.json"));
+        let level_data = LevelData { ingredients: &ingredients, recipes: &recipes, archetypes: &archetypes, presets: &presets };
+        let levels = BUILTIN_LEVELS.iter()
+            .map(|(id, json)| Level::parse(id, json, &level_data)
+                 .unwrap_or_else(|e| panic!("built in level {} is invalid: {}", id, e)))
+            .collect();
+        let upgrades = UpgradeShop::new(parse_builtin(
+            "upgrades.json", include_str!("../resources/data/upgrades.json")))
+            .expect("built in upgrades are valid");
+        GameData::new(ingredients, recipes, archetypes, presets, levels, upgrades)
+            .expect("built in presets are valid")
+    }
+
+    /// load the data files in `dir`
+    pub fn load(dir: &Path) -> Result<GameData, DataError> {
+        let presets_path = dir.join("presets.json");
+        let ingredients = IngredientBook::load(&dir.join("ingredients.json"))?;
+        let recipes = RecipeBook::load(&dir.join("recipes.json"), &ingredients)?;
+        let archetypes = ArchetypeTable::load(&dir.join("archetypes.json"))?;
+        let presets: Vec<Preset> = load_json(&presets_path)?;
+        let levels = load_levels(&dir.join("levels"),
+                                 &LevelData { ingredients: &ingredients, recipes: &recipes,
+                                              archetypes: &archetypes, presets: &presets })?;
+        let upgrades = UpgradeShop::load(&dir.join("upgrades.json"))?;
+        GameData::new(ingredients, recipes, archetypes, presets, levels, upgrades)
+            .map_err(|e| DataError::Invalid(presets_path.display().to_string(), e))
+    }
+
+    fn new(ingredients: IngredientBook, recipes: RecipeBook, archetypes: ArchetypeTable, presets: Vec<Preset>,
+           levels: Vec<Level>, upgrades: UpgradeShop) -> Result<GameData, String> {
+        for p in presets.iter() {
+            p.validate()?;
+            if let Some(id) = p.spawn_table.keys().find(|id| archetypes.find(id).is_none()) {
+                return Err(format!("preset {} spawns unknown archetype {}", p.id, id));
+            }
+            p.queue.validate(&ingredients).map_err(|e| format!("preset {}: {}", p.id, e))?;
+        }
+        if !presets.iter().any(|p| p.id == DEFAULT_PRESET) {
+            return Err(format!("there must be a {} preset", DEFAULT_PRESET));
+        }
+        Ok(GameData { ingredients, recipes, archetypes, presets, levels, upgrades })
+    }
+
+    /// the rules for the preset with this id
+    pub fn rules(&self, preset: &str) -> Option<Rules> {
+        self.presets.iter().find(|p| p.id == preset).map(|p| Rules {
+            ingredients: self.ingredients.clone(),
+            recipes: self.recipes.clone(),
+            archetypes: self.archetypes.clone(),
+            preset: p.clone(),
+            level: None,
+            upgrades: self.upgrades.clone(),
+        })
+    }
+
+    /// the rules for the level with this id, played with the level's preset
+    pub fn level_rules(&self, level: &str) -> Option<Rules> {
+        let level = self.levels.iter().find(|l| l.id == level)?;
+        let mut rules = self.rules(&level.preset)?;
+        rules.level = Some(level.clone());
+        Some(rules)
+    }
+
+    pub fn default_rules(&self) -> Rules {
+        self.rules(DEFAULT_PRESET).expect("default preset exists")
+    }
+}
@@ -1,42 +1,31 @@
-use std::{collections::{VecDeque, HashMap, vec_deque::Iter}, path::Path, slice::IterMut};
+use std::{collections::VecDeque, slice::IterMut};
 
-use nze_game_sdl::{Render, Camera, Error, GameObject, geometry::Vec2};
 use rand::prelude::*;
-
-use crate::moving_target::Target;
-
-#[derive(Hash, Eq, PartialEq, Clone, Copy)]
-pub enum Ingredients {
-    Bread,
-    Lettuce,
-    Meat,
-    Tomato,
-}
-
-pub const INGREDIENT_COUNT: usize = 4;
-
-pub fn get_ingredient(index: usize) -> Ingredients{
-    match index {
-        0 => Ingredients::Bread,
-        1 => Ingredients::Lettuce,
-        2 => Ingredients::Meat,
-        3 => Ingredients::Tomato,
-        _ => panic!("index of ingredient out of range"),
-    }
-}
-
-pub fn get_rand_ingredient(rng: &mut ThreadRng) -> Ingredients {
-    get_ingredient((rng.gen::<f64>() * INGREDIENT_COUNT as f64) as usize)
-}
-
-#[derive(Clone)]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    geometry::Vec2,
+    moving_target::Target,
+    sim::GameRng,
+    event::GameEvent,
+    ingredient::{Ingredient, IngredientBook},
+    generator::QueueGenerator,
+};
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Sandwitch {
-    pub ingredients : VecDeque<Ingredients>,
+    pub ingredients : VecDeque<Ingredient>,
     pub ing_targets: VecDeque<Target>,
     pub target: Target,
     sw_dir: f64,
 }
 
+impl Default for Sandwitch {
+    fn default() -> Sandwitch {
+        Sandwitch::new()
+    }
+}
+
 impl Sandwitch {
     pub fn new() -> Sandwitch {
         Sandwitch {
@@ -47,14 +36,14 @@ impl Sandwitch {
         }
     }
 
-    pub fn add_back(&mut self, ingredient: Ingredients, t: Target) {
+    pub fn add_back(&mut self, ingredient: Ingredient, t: Target) {
         self.ingredients.push_back(ingredient);
         let mut t = t;
         t.speed = self.target.speed;
         self.ing_targets.push_back(t);
     }
 
-    pub fn add(&mut self, ingredient: Ingredients, rng: &mut ThreadRng) {
+    pub fn add<R: Rng + ?Sized>(&mut self, ingredient: Ingredient, rng: &mut R) {
         self.ingredients.push_front(ingredient);
         self.ing_targets.push_front(Target::new_with_speed(
             self.target.speed,
@@ -66,7 +55,7 @@ impl Sandwitch {
         ));
     }
 
-    pub fn take(&mut self) -> Option<(Ingredients, Target)> {
+    pub fn take(&mut self) -> Option<(Ingredient, Target)> {
         let ing = self.ingredients.pop_back();
         let tar = self.ing_targets.pop_back();
         if ing.is_none() || tar.is_none() {
@@ -76,10 +65,11 @@ impl Sandwitch {
     }
         
 
-    pub fn reset(&mut self) {
+    /// empty the plate, leaving only `bun` at the bottom
+    pub fn reset(&mut self, bun: Ingredient) {
         self.ingredients.clear();
         self.ing_targets.clear();
-        self.add_back(Ingredients::Bread, self.target);
+        self.add_back(bun, self.target);
     }
 
     pub fn clear(&mut self) {
@@ -107,7 +97,12 @@ impl Sandwitch {
     }
 
     fn get_ing_yoff(dir: f64, i: f64) -> f64 {
-       dir * (i as f64 * (ING_SIZE.y + QUEUE_ING_SPACING))
+       dir * (i * (ING_SIZE.y + QUEUE_ING_SPACING))
+    }
+
+    /// if every ingredient has landed
+    pub fn settled(&self) -> bool {
+        self.ing_targets.iter().all(|t| t.is_active())
     }
 
     pub fn set_speed(&mut self, speed: f64) {
@@ -126,35 +121,73 @@ impl Sandwitch {
 }
 
 const QUEUE_SPEED: f64 = 400.0;
+/// machine operations remembered for undoing
+const UNDO_LIMIT: usize = 10;
+
+/// A change to the machine that can be undone.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+enum Op {
+    /// `can_hold` is from before the release
+    Release { plate: usize, can_hold: bool },
+    Bin { plate: usize },
+    Switch { from: usize },
+    /// `swapped` if there was already an ingredient held
+    Hold { swapped: bool },
+}
+/// including the bin, upgrades can add more
+const PLATE_COUNT: usize = 6;
 
+#[derive(Serialize, Deserialize)]
 pub struct SandwitchMachine {
-    sandwitches: Vec<Sandwitch>,
+    pub(crate) sandwitches: Vec<Sandwitch>,
     queue_size: usize,
-    queue: Sandwitch,
+    pub(crate) queue: Sandwitch,
+    generator: QueueGenerator,
+    /// what is left of the generator's current bag
+    bag: Vec<Ingredient>,
+    /// ingredients the waiting customers still need, for the generator
+    #[serde(skip)]
+    demand: Vec<Ingredient>,
+    /// one ingredient put aside from the queue, it follows the chef
+    pub(crate) hold: Sandwitch,
+    /// holding is allowed once per release, so it can't swap back and forth
+    can_hold: bool,
+    /// the latest operations, newest at the back
+    history: VecDeque<Op>,
     active: usize,
-    rng: ThreadRng,
-    delicat_target: Target,
+    rng: GameRng,
+    pub(crate) delicat_target: Target,
+    #[serde(skip)]
+    events: Vec<GameEvent>,
 }
 
 impl SandwitchMachine {
-    pub fn new() -> SandwitchMachine {
+    pub fn new(rng: GameRng, book: &IngredientBook, queue_size: usize,
+               generator: QueueGenerator) -> SandwitchMachine {
         let mut sm = SandwitchMachine {
-            queue_size: 6,
+            queue_size,
             sandwitches: Vec::new(),
             queue: Sandwitch::new(),
+            generator,
+            bag: Vec::new(),
+            demand: Vec::new(),
+            hold: Sandwitch::new(),
+            can_hold: true,
+            history: VecDeque::new(),
             active: 0,
-            rng:thread_rng(),
+            rng,
             delicat_target: Target::new(),
+            events: Vec::new(),
         };
         sm.delicat_target.breath = true;
         sm.delicat_target.set_target(DELICAT_LOCATION);
         sm.delicat_target.breath_speed /= 2.5;
         sm.delicat_target.breath_size.y = 1.5;
-        for _ in 0..sm.queue_size {
+        for _ in 0..PLATE_COUNT {
             sm.sandwitches.push(Sandwitch::new());
         }
         for s in sm.sandwitches.iter_mut() {
-            s.reset();
+            s.reset(book.base_bun());
         }
         sm.queue.set_speed(QUEUE_SPEED);
         sm.queue.set_target(sm.get_queue_target());
@@ -162,44 +195,111 @@ impl SandwitchMachine {
         sm.queue.target.breath_size = Vec2::new(0.0, 4.0);
         sm.queue.target.breath_speed = 0.5;
         sm.queue.sw_dir = 1.0;
-        sm.fill_queue();
+        sm.hold.set_speed(QUEUE_SPEED);
+        sm.hold.set_target(sm.get_hold_target());
+        sm.fill_queue(book);
         sm
     }
 
     fn get_queue_target(&self) -> Vec2 {
-        Vec2::new(QUEUE_BASE.x + QUEUE_MOVE * self.active as f64,
+        Vec2::new(QUEUE_BASE.x + self.plate_spacing() * self.active as f64,
                   QUEUE_BASE.y
         )
     }
 
+    /// beside the queue, on the left over the last plate so it stays on screen
+    fn get_hold_target(&self) -> Vec2 {
+        let queue = self.get_queue_target();
+        let side = if self.active + 1 < self.sandwitches.len() { 1.0 } else { -1.0 };
+        Vec2::new(queue.x + side * HOLD_OFFSET.x, queue.y + HOLD_OFFSET.y)
+    }
+
+    /// plates are squeezed together when there are more than `PLATE_COUNT`
+    fn plate_spacing(&self) -> f64 {
+        QUEUE_MOVE * (PLATE_COUNT as f64 / self.sandwitches.len() as f64).min(1.0)
+    }
+
+    /// add `count` empty plates to the right
+    pub(crate) fn add_plates(&mut self, count: usize, book: &IngredientBook) {
+        for _ in 0..count {
+            let mut sw = Sandwitch::new();
+            sw.reset(book.base_bun());
+            self.sandwitches.push(sw);
+        }
+    }
+
+    /// let `extra` more ingredients wait in the queue
+    pub(crate) fn grow_queue(&mut self, extra: usize, book: &IngredientBook) {
+        self.queue_size += extra;
+        self.fill_queue(book);
+    }
+
+    /// the chef moves at `multiplier` times the normal speed
+    pub(crate) fn set_chef_speed(&mut self, multiplier: f64) {
+        self.queue.set_speed(QUEUE_SPEED * multiplier);
+        self.hold.set_speed(QUEUE_SPEED * multiplier);
+    }
+
     pub fn update(&mut self, dt: f64) {
+        let spacing = self.plate_spacing();
         for (i, sm) in self.sandwitches.iter_mut().enumerate() {
             sm.update(dt);
             sm.set_target(
-                Vec2::new(SANDWITCH_BASE.x + (i as f64 * QUEUE_MOVE), SANDWITCH_BASE.y)
+                Vec2::new(SANDWITCH_BASE.x + (i as f64 * spacing), SANDWITCH_BASE.y)
             );
         }
         self.sandwitches[0].clear();
         self.queue.set_target(self.get_queue_target());
         self.queue.update(dt);
+        self.hold.set_target(self.get_hold_target());
+        self.hold.update(dt);
         self.queue.target.breath_update(dt);
         self.delicat_target.breath_update(dt);
     }
 
-    pub fn fill_queue(&mut self) {
+    pub fn fill_queue(&mut self, book: &IngredientBook) {
         while self.queue.ingredients.len() < self.queue_size {
-            self.queue.add(get_rand_ingredient(&mut self.rng), &mut self.rng);
+            let ing = self.generator.next(&mut self.rng, book, &mut self.bag, &self.demand);
+            self.queue.add(ing, &mut self.rng);
         }
     }
 
-    pub fn release(&mut self) {
+    pub fn release(&mut self, book: &IngredientBook) {
         let (i, t) = self.queue.take().unwrap();
         self.sandwitches[self.active].add_back(i, t);
-        self.fill_queue();
+        self.events.push(GameEvent::IngredientReleased { ingredient: i, plate: self.active });
+        if self.active == 0 {
+            // the bin empties itself, so nothing before this can be undone
+            self.history.clear();
+        } else {
+            self.remember(Op::Release { plate: self.active, can_hold: self.can_hold });
+        }
+        self.can_hold = true;
+        self.fill_queue(book);
+    }
+
+    /// put the next ingredient in the hold slot, swapping it with the held one if there is one
+    pub fn hold(&mut self, book: &IngredientBook) {
+        if !self.can_hold {
+            return;
+        }
+        let (i, t) = self.queue.take().unwrap();
+        let swapped = match self.hold.take() {
+            Some((held, held_t)) => {
+                self.queue.add_back(held, held_t);
+                true
+            },
+            None => false,
+        };
+        self.hold.add_back(i, t);
+        self.can_hold = false;
+        self.remember(Op::Hold { swapped });
+        self.events.push(GameEvent::IngredientHeld { ingredient: i });
+        self.fill_queue(book);
     }
 
     pub fn bin(&mut self) {
-        if self.sandwitches[self.active].ingredients.len() > 0 {
+        if !self.sandwitches[self.active].ingredients.is_empty() {
            // if self.sandwitches[self.active].ingredients.len() == 1 && self.active != 0 {
            //     return;
            // }
@@ -207,123 +307,313 @@ impl SandwitchMachine {
             self.queue.add_back(
                 i, t
             );
+            self.events.push(GameEvent::IngredientBinned { ingredient: i, plate: self.active });
+            self.remember(Op::Bin { plate: self.active });
         }
     }
 
     pub fn switch(&mut self, diff: i32) {
-        let new = self.active as i32 + diff;
-        if new < 0 {
-            self.active = self.sandwitches.len() - new.abs() as usize;
-        } else {
-            self.active = new as usize % self.sandwitches.len();
+        let from = self.active;
+        // wraps around the plates however far it goes
+        self.active = (self.active as i32 + diff).rem_euclid(self.sandwitches.len() as i32) as usize;
+        self.events.push(GameEvent::PlateSwitched { from, to: self.active });
+        self.remember(Op::Switch { from });
+    }
+
+    fn remember(&mut self, op: Op) {
+        if self.history.len() == UNDO_LIMIT {
+            self.history.pop_front();
         }
+        self.history.push_back(op);
+    }
+
+    /// set what the waiting customers still need, for a demand aware generator
+    pub(crate) fn set_demand(&mut self, demand: Vec<Ingredient>) {
+        self.demand = demand;
+    }
+
+    /// forget the operations so far, once a plate is served they can't be undone
+    pub(crate) fn clear_history(&mut self) {
+        self.history.clear();
+    }
+
+    /// rewind the latest release, bin, switch or hold, false if there is nothing to undo.
+    /// Ingredients go back to the front of the queue, which may leave it longer than usual
+    pub fn undo(&mut self) -> bool {
+        let op = match self.history.pop_back() {
+            Some(op) => op,
+            None => return false,
+        };
+        match op {
+            Op::Release { plate, can_hold } => {
+                if let Some((i, t)) = self.sandwitches[plate].take() {
+                    self.queue.add_back(i, t);
+                }
+                self.can_hold = can_hold;
+            },
+            Op::Bin { plate } => {
+                if let Some((i, t)) = self.queue.take() {
+                    self.sandwitches[plate].add_back(i, t);
+                }
+            },
+            Op::Switch { from } => self.active = from,
+            Op::Hold { swapped } => {
+                let held = self.hold.take();
+                if swapped {
+                    if let Some((i, t)) = self.queue.take() {
+                        self.hold.add_back(i, t);
+                    }
+                }
+                if let Some((i, t)) = held {
+                    self.queue.add_back(i, t);
+                }
+                self.can_hold = true;
+            },
+        }
+        self.events.push(GameEvent::Undone);
+        true
+    }
+
+    /// the events since this was last called
+    pub fn take_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
     }
 
-    pub fn sandwitches(&mut self) -> IterMut<Sandwitch> {
+    pub fn sandwitches(&mut self) -> IterMut<'_, Sandwitch> {
         self.sandwitches.iter_mut()
     }
-    
-}
 
-pub struct SandwitchRender {
-    ingredient: HashMap<Ingredients, GameObject>,
-    chef: GameObject,
-    restauraunt_front: GameObject,
-    delicat: GameObject,
-    plate: GameObject,
+    /// the plates in order, plate 0 is the bin
+    pub fn plates(&self) -> &[Sandwitch] {
+        &self.sandwitches
+    }
+
+    /// the ingredients waiting to be released, the next one is at the back
+    pub fn queue(&self) -> &Sandwitch {
+        &self.queue
+    }
+
+    /// the ingredient in the hold slot
+    pub fn held(&self) -> Option<Ingredient> {
+        self.hold.ingredients.back().copied()
+    }
+
+    /// if `hold` will do anything, false until the next release after holding
+    pub fn can_hold(&self) -> bool {
+        self.can_hold
+    }
+
+    pub fn active(&self) -> usize {
+        self.active
+    }
+
+    /// the plate the chef is over, none over the bin
+    pub(crate) fn active_plate(&mut self) -> Option<&mut Sandwitch> {
+        if self.active == 0 {
+            None
+        } else {
+            self.sandwitches.get_mut(self.active)
+        }
+    }
 }
 
 pub const ING_SIZE: Vec2 = Vec2::new(46.0, 24.0);
 const QUEUE_BASE: Vec2 = Vec2::new(30.0, 20.0);
-const CHEF_OFFSET: Vec2 = Vec2::new(-20.0, 100.0);
 const QUEUE_MOVE: f64 = ING_SIZE.x * 1.6;
 const QUEUE_ING_SPACING: f64 = -ING_SIZE.y * 0.5;
 pub const SANDWITCH_BASE: Vec2 = Vec2::new(QUEUE_BASE.x, QUEUE_BASE.y + ING_SIZE.y * 8.0);
 
+/// from the top of the queue to the hold slot
+const HOLD_OFFSET: Vec2 = Vec2::new(ING_SIZE.x * 1.2, ING_SIZE.y * 3.0);
+
 const DELICAT_LOCATION: Vec2 = Vec2::new(0.0, 210.0);
-const PLATE_OFFSET: Vec2 = Vec2::new(-2.0, 11.0);
-
-impl SandwitchRender {
-    pub fn new(render: &mut Render) -> Result<SandwitchRender, Error> {
-        Ok(SandwitchRender {
-            ingredient: Self::get_ingredient_hash(render)?,
-            chef: GameObject::new_from_tex(render.texture_manager.load(Path::new("resources/textures/chef.png"))?),
-            delicat: GameObject::new_from_tex(render.texture_manager.load(Path::new("resources/textures/deli-cat.png"))?),
-            restauraunt_front: GameObject::new_from_tex(render.texture_manager.load(
-                Path::new("resources/textures/restaurant_front.png"))?),
-            plate: GameObject::new_from_tex(render.texture_manager.load(
-                Path::new("resources/textures/plate.png"))?),
-        })
-    }
-
-    pub fn draw(&mut self, cam: &mut Camera, machine: &SandwitchMachine) {
-        self.chef.rect.x = machine.queue.target.get_pos().x + CHEF_OFFSET.x;
-        self.chef.rect.y = machine.queue.target.get_pos().y + CHEF_OFFSET.y;
-        cam.draw(&self.chef);
-        cam.draw(&self.restauraunt_front);
-        let delicat_pos = machine.delicat_target.get_pos();
-        self.delicat.rect.x = delicat_pos.x;
-        self.delicat.rect.y = delicat_pos.y;
-        cam.draw(&self.delicat);
-        self.render_sw(cam, &machine.queue);
-
-        for (i, sw) in machine.sandwitches.iter().enumerate() {
-            if i != 0 {
-                let mut p = self.plate.clone();
-                let pos = sw.target.get_pos();
-                p.rect.x = pos.x + PLATE_OFFSET.x;
-                p.rect.y = pos.y + PLATE_OFFSET.y;
-                cam.draw(&p);
-            }
-            self.render_sw(cam, &sw);
-        }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::GameData;
+
+    const QUEUE_SIZE: usize = 6;
+
+    /// the plates, the queue, the held ingredient, `can_hold` and the active plate
+    #[derive(Clone, PartialEq, Debug)]
+    struct State {
+        plates: Vec<Vec<Ingredient>>,
+        queue: Vec<Ingredient>,
+        held: Option<Ingredient>,
+        can_hold: bool,
+        active: usize,
     }
 
-    pub fn render_sw(&self, cam: &mut Camera, sw: &Sandwitch) {
-        for (i, ing) in sw.ingredients.iter().enumerate() {
-            let mut ing = self.ingredient.get(&ing).unwrap().clone();
-            ing.rect.w = ING_SIZE.x;
-            ing.rect.h = ING_SIZE.y;
-            ing.rect.x = sw.ing_targets[i].get_pos_no_offset().x;
-            ing.rect.y = sw.ing_targets[i].get_pos_no_offset().y;
-            cam.draw(&ing);
+    fn state(sm: &SandwitchMachine) -> State {
+        State {
+            plates: sm.plates().iter().map(|p| p.ingredients.iter().copied().collect()).collect(),
+            queue: sm.queue().ingredients.iter().copied().collect(),
+            held: sm.held(),
+            can_hold: sm.can_hold(),
+            active: sm.active(),
         }
     }
 
-    pub fn render_ings(&self, cam: &mut Camera, ings: Iter<Ingredients>,
-                            base: Vec2, x_off: f64, ing_size: Vec2, ing_spacing: f64, dir_mod: f64
-    ) {
-        for (i, ing) in ings.enumerate() {
-            let mut ing = self.ingredient.get(&ing).unwrap().clone();
-            ing.rect.w = ing_size.x;
-            ing.rect.h = ing_size.y;
-            ing.rect.x = base.x + x_off;
-            ing.rect.y = base.y + dir_mod * (i as f64 * (ing_size.y + ing_spacing));
-            cam.draw(&ing);
+    /// a machine over the first plate with two ingredients on it
+    fn machine(book: &IngredientBook) -> SandwitchMachine {
+        let mut sm = SandwitchMachine::new(GameRng::seed_from_u64(0), book, QUEUE_SIZE, QueueGenerator::default());
+        sm.switch(1);
+        sm.release(book);
+        sm.release(book);
+        sm
+    }
+
+    #[test]
+    fn the_first_hold_takes_the_next_ingredient() {
+        let book = GameData::builtin().ingredients;
+        let mut sm = machine(&book);
+        let before = state(&sm);
+        assert_eq!(sm.held(), None);
+        sm.hold(&book);
+        assert_eq!(sm.held(), before.queue.last().copied());
+        assert_eq!(sm.queue().ingredients.len(), QUEUE_SIZE);
+        assert!(sm.queue().ingredients.iter().skip(1).eq(before.queue[..QUEUE_SIZE - 1].iter()));
+        assert_eq!(state(&sm).plates, before.plates);
+        assert!(!sm.can_hold());
+        assert_eq!(sm.take_events().last(), Some(&GameEvent::IngredientHeld { ingredient: sm.held().unwrap() }));
+    }
+
+    #[test]
+    fn holding_again_swaps_with_the_held_ingredient() {
+        let book = GameData::builtin().ingredients;
+        let mut sm = machine(&book);
+        sm.hold(&book);
+        sm.release(&book);
+        let before = state(&sm);
+        sm.hold(&book);
+        assert_eq!(sm.held(), before.queue.last().copied());
+        assert_eq!(sm.queue().ingredients.back().copied(), before.held);
+        assert_eq!(sm.queue().ingredients.len(), QUEUE_SIZE);
+    }
+
+    #[test]
+    fn hold_only_works_once_per_release() {
+        let book = GameData::builtin().ingredients;
+        let mut sm = machine(&book);
+        sm.hold(&book);
+        sm.take_events();
+        let before = state(&sm);
+        sm.hold(&book);
+        assert_eq!(state(&sm), before);
+        assert!(sm.take_events().is_empty());
+        // binning doesn't unlock it, only releasing
+        sm.bin();
+        assert!(!sm.can_hold());
+        sm.release(&book);
+        assert!(sm.can_hold());
+    }
+
+    #[test]
+    fn switch_wraps_around_any_distance() {
+        let book = GameData::builtin().ingredients;
+        let mut sm = machine(&book);
+        let plates = sm.plates().len() as i32;
+        sm.switch(-1);
+        assert_eq!(sm.active(), 0);
+        sm.switch(-1);
+        assert_eq!(sm.active(), plates as usize - 1);
+        for diff in [-plates - 1, -plates * 3 + 2, plates + 1, plates * 5 - 1, -1000, 1000, i32::MIN / 2, i32::MAX / 2] {
+            let from = sm.active() as i32;
+            sm.switch(diff);
+            assert_eq!(sm.active() as i32, (from + diff).rem_euclid(plates), "switching by {}", diff);
         }
     }
 
-    fn get_ingredient_hash(render: &mut Render) -> Result<HashMap<Ingredients, GameObject>, Error> {
-        let mut textures = HashMap::new();
-        Self::add_ing_to_hashmap(
-            render, &mut textures,
-            Ingredients::Bread, Path::new("resources/textures/ingredient/bread.png"))?;
-        Self::add_ing_to_hashmap(
-            render, &mut textures,
-            Ingredients::Lettuce, Path::new("resources/textures/ingredient/lettuce.png"))?;
-        Self::add_ing_to_hashmap(
-            render, &mut textures,
-            Ingredients::Meat, Path::new("resources/textures/ingredient/patty.png"))?;
-        Self::add_ing_to_hashmap(
-            render, &mut textures,
-            Ingredients::Tomato, Path::new("resources/textures/ingredient/tomato.png"))?;
-        Ok(textures)
-    }
-
-    fn add_ing_to_hashmap(render: &mut Render, ingredients: &mut HashMap<Ingredients, GameObject>,
-                          ing: Ingredients, res: &Path) -> Result<(), Error> {
-        ingredients.insert(ing,
-                           GameObject::new_from_tex(render.texture_manager.load(res)?));
-        Ok(())
+    #[test]
+    fn undo_switch() {
+        let book = GameData::builtin().ingredients;
+        let mut sm = machine(&book);
+        let before = state(&sm);
+        sm.switch(-2);
+        assert_eq!(sm.active(), sm.plates().len() - 1);
+        assert!(sm.undo());
+        assert_eq!(state(&sm), before);
+    }
+
+    #[test]
+    fn undo_bin() {
+        let book = GameData::builtin().ingredients;
+        let mut sm = machine(&book);
+        let before = state(&sm);
+        sm.bin();
+        assert_eq!(sm.plates()[1].ingredients.len(), 2);
+        assert!(sm.undo());
+        assert_eq!(state(&sm), before);
+    }
+
+    #[test]
+    fn undo_swapping_the_held_ingredient() {
+        let book = GameData::builtin().ingredients;
+        let mut sm = machine(&book);
+        sm.hold(&book);
+        sm.release(&book);
+        let before = state(&sm);
+        sm.hold(&book);
+        assert_eq!(sm.held(), before.queue.last().copied());
+        assert_eq!(sm.queue().ingredients.back().copied(), before.held);
+        assert!(!sm.can_hold());
+        assert!(sm.undo());
+        assert_eq!(state(&sm), before);
+    }
+
+    /// the released or held ingredient goes back on top of the queue, and the one
+    /// dealt to refill it stays, so the queue is left one longer until it's used
+    fn assert_rewound(sm: &SandwitchMachine, before: &State) {
+        let after = state(sm);
+        assert_eq!(after.queue.len(), QUEUE_SIZE + 1);
+        assert_eq!(after.queue[1..], before.queue[..]);
+        assert_eq!(State { queue: before.queue.clone(), ..after }, *before);
+    }
+
+    #[test]
+    fn undo_release() {
+        let book = GameData::builtin().ingredients;
+        let mut sm = machine(&book);
+        sm.hold(&book);
+        let before = state(&sm);
+        assert!(!before.can_hold);
+        sm.release(&book);
+        assert!(sm.can_hold());
+        assert!(sm.undo());
+        assert_rewound(&sm, &before);
+
+        // the queue isn't topped up again until it is back below its size
+        sm.release(&book);
+        assert_eq!(sm.queue().ingredients.len(), QUEUE_SIZE);
+    }
+
+    #[test]
+    fn undo_hold() {
+        let book = GameData::builtin().ingredients;
+        let mut sm = machine(&book);
+        let before = state(&sm);
+        sm.hold(&book);
+        assert_eq!(sm.held(), before.queue.last().copied());
+        assert!(sm.undo());
+        assert_rewound(&sm, &before);
+    }
+
+    #[test]
+    fn undo_stops_at_the_limit_and_at_the_bin() {
+        let book = GameData::builtin().ingredients;
+        let mut sm = machine(&book);
+        for _ in 0..UNDO_LIMIT + 2 {
+            sm.switch(1);
+        }
+        for _ in 0..UNDO_LIMIT {
+            assert!(sm.undo());
+        }
+        assert!(!sm.undo());
+
+        sm.switch(-(sm.active() as i32));
+        sm.release(&book);
+        assert!(!sm.undo());
     }
 }
@@ -0,0 +1,62 @@
+use nze_game_sdl::{Camera, Colour, geometry::{Vec2, Rect}};
+
+use crate::{game::Context, VIEW_WIDTH, VIEW_HEIGHT};
+use super::{Scene, Transition, Title, Shop, menu::Menu, money};
+
+const NEXT_DAY: usize = 0;
+const QUIT: usize = 1;
+
+/// How the day went, shown at closing time before the next day starts.
+pub struct DayReport {
+    menu: Menu,
+}
+
+impl DayReport {
+    pub fn new() -> DayReport {
+        DayReport { menu: Menu::new(&["Next Day", "Quit To Title"]) }
+    }
+}
+
+impl Scene for DayReport {
+    fn update(&mut self, ctx: &mut Context, _frame_elapsed: f64) -> Transition {
+        match self.menu.update(&ctx.input) {
+            Some(NEXT_DAY) if ctx.sim.rules().upgrades.is_empty() => {
+                ctx.sim.next_day();
+                Transition::Pop
+            },
+            Some(NEXT_DAY) => Transition::Replace(Box::new(Shop::new(ctx))),
+            Some(QUIT) => Transition::Clear(Box::new(Title::new())),
+            _ => Transition::None,
+        }
+    }
+
+    fn draw(&mut self, ctx: &mut Context, cam: &mut Camera) {
+        cam.draw_rect(Rect::new(0.0, 0.0, VIEW_WIDTH, VIEW_HEIGHT),
+                      Colour::new(0, 0, 0, 160), Vec2::zero());
+        let font = &ctx.assets.font;
+        cam.draw_disposable_text(font, format!("Day {} Over", ctx.sim.day()), TITLE_SIZE,
+                                 Vec2::new(100.0, 30.0), Colour::white(), Vec2::zero());
+        let stats = ctx.sim.day_stats();
+        let lines = [
+            format!("Customers Served: {}", stats.served),
+            format!("Customers Lost: {}", stats.angry),
+            format!("Earned: {}", money(stats.earnings)),
+            format!("Best Streak: {}", stats.best_streak),
+            format!("Ingredients Wasted: {}", stats.wasted),
+        ];
+        for (i, line) in lines.into_iter().enumerate() {
+            cam.draw_disposable_text(font, line, LINE_SIZE,
+                                     Vec2::new(150.0, 120.0 + LINE_SPACING * i as f64),
+                                     Colour::white(), Vec2::zero());
+        }
+        self.menu.draw(ctx, cam, Vec2::new(150.0, 280.0));
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
+
+const TITLE_SIZE: u32 = 50;
+const LINE_SIZE: u32 = 20;
+const LINE_SPACING: f64 = 28.0;
@@ -0,0 +1,74 @@
+use nze_game_sdl::{Camera, Colour, geometry::Vec2};
+
+use crate::{game::Context, VIEW_HEIGHT};
+use super::{Scene, Transition, money};
+
+/// Slides the end screen down over the finished game.
+pub struct GameOver;
+
+impl GameOver {
+    pub fn new() -> GameOver {
+        GameOver
+    }
+}
+
+impl Scene for GameOver {
+    fn enter(&mut self, ctx: &mut Context) {
+        ctx.assets.end_screen.rect.y = - VIEW_HEIGHT;
+    }
+
+    fn update(&mut self, ctx: &mut Context, frame_elapsed: f64) -> Transition {
+        let end_screen = &mut ctx.assets.end_screen;
+        if end_screen.rect.y == 0.0 {
+            if ctx.input.down.down(true) {
+                ctx.new_game();
+                return Transition::Pop;
+            }
+        } else {
+            end_screen.rect.y += frame_elapsed * 100.0;
+            if end_screen.rect.y > 0.0 {
+                end_screen.rect.y = 0.0;
+            }
+        }
+        Transition::None
+    }
+
+    fn draw(&mut self, ctx: &mut Context, cam: &mut Camera) {
+        let assets = &ctx.assets;
+        cam.draw(&assets.end_screen);
+        cam.draw_disposable_text(&assets.font, format!("Score: {}", ctx.sim.score()),
+                                 FINAL_SCORE_SIZE * 3,
+                                 Vec2::new(assets.end_screen.rect.x + 30.0,
+                                           assets.end_screen.rect.y + 120.0),
+                                 Colour::new(0, 0, 0, 255), Vec2::new(1.0, 1.0));
+        cam.draw_disposable_text(&assets.font,
+                                 format!("Earned {}, {} In Tips", money(ctx.sim.earnings()), money(ctx.sim.tips())),
+                                 FINAL_SCORE_SIZE,
+                                 Vec2::new(assets.end_screen.rect.x + 60.0,
+                                           assets.end_screen.rect.y + 200.0),
+                                 Colour::new(0, 0, 0, 255), Vec2::new(1.0, 1.0));
+        cam.draw_disposable_text(&assets.font, "Press Down To Play Again".to_string(),
+                                 FINAL_SCORE_SIZE,
+                                 Vec2::new(assets.end_screen.rect.x + 90.0,
+                                           assets.end_screen.rect.y + 270.0),
+                                 Colour::new(0, 0, 0, 255), Vec2::new(1.0, 1.0));
+        if let Some(level) = &ctx.sim.rules().level {
+            let result = if ctx.sim.is_won() {
+                format!("{} Complete!", level.name)
+            } else {
+                format!("{} Failed", level.name)
+            };
+            cam.draw_disposable_text(&assets.font, result, FINAL_SCORE_SIZE,
+                                     Vec2::new(assets.end_screen.rect.x + 90.0,
+                                               assets.end_screen.rect.y + 90.0),
+                                     Colour::new(0, 0, 0, 255), Vec2::new(1.0, 1.0));
+        }
+        cam.draw(&assets.end_sign);
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
+
+const FINAL_SCORE_SIZE: u32 = 20;
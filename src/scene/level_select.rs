@@ -0,0 +1,58 @@
+use nze_game_sdl::{Camera, Colour, geometry::Vec2};
+
+use crate::game::Context;
+use super::{Scene, Transition, Title, Playing, menu::Menu};
+
+/// Picks a scripted level to play.
+pub struct LevelSelect {
+    menu: Menu,
+}
+
+impl LevelSelect {
+    pub fn new(ctx: &Context) -> LevelSelect {
+        let names: Vec<&str> = ctx.data.levels.iter().map(|l| l.name.as_str()).collect();
+        let mut menu = Menu::new(&names);
+        // start on the level played last
+        if let Some(level) = &ctx.rules.level {
+            if let Some(i) = ctx.data.levels.iter().position(|l| l.id == level.id) {
+                menu.select(i);
+            }
+        }
+        LevelSelect { menu }
+    }
+}
+
+impl Scene for LevelSelect {
+    fn update(&mut self, ctx: &mut Context, _frame_elapsed: f64) -> Transition {
+        if ctx.input.pause.down(true) || ctx.input.up.down(true) || ctx.data.levels.is_empty() {
+            return Transition::Replace(Box::new(Title::new()));
+        }
+        match self.menu.update(&ctx.input) {
+            Some(i) => {
+                ctx.rules = ctx.data.level_rules(&ctx.data.levels[i].id).expect("level is in the data");
+                ctx.new_game();
+                Transition::Replace(Box::new(Playing::new()))
+            },
+            None => Transition::None,
+        }
+    }
+
+    fn draw(&mut self, ctx: &mut Context, cam: &mut Camera) {
+        cam.draw(&ctx.assets.bg);
+        cam.draw_rect(ctx.assets.bg.rect, Colour::new(0, 0, 0, 150), Vec2::zero());
+        cam.draw_disposable_text(&ctx.assets.font, "Levels".to_string(), TITLE_SIZE,
+                                 Vec2::new(140.0, 40.0), Colour::white(), Vec2::zero());
+        self.menu.draw(ctx, cam, Vec2::new(120.0, 130.0));
+        if let Some(level) = ctx.data.levels.get(self.menu.selected()) {
+            cam.draw_disposable_text(&ctx.assets.font,
+                                     format!("{}, serve {}", level.description, level.goal),
+                                     HINT_SIZE, Vec2::new(60.0, 290.0),
+                                     Colour::new(255, 220, 120, 255), Vec2::zero());
+        }
+        cam.draw_disposable_text(&ctx.assets.font, "Down To Start, Up To Go Back".to_string(),
+                                 HINT_SIZE, Vec2::new(80.0, 320.0), Colour::white(), Vec2::zero());
+    }
+}
+
+const TITLE_SIZE: u32 = 50;
+const HINT_SIZE: u32 = 16;
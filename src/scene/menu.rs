@@ -0,0 +1,57 @@
+use nze_game_sdl::{Camera, Colour, geometry::Vec2};
+
+use crate::{game::Context, input::Input};
+
+const ITEM_SIZE: u32 = 20;
+const ITEM_SPACING: f64 = 30.0;
+
+/// A list of options, left and right move the selection and down picks it.
+pub struct Menu {
+    items: Vec<String>,
+    selected: usize,
+}
+
+impl Menu {
+    pub fn new(items: &[&str]) -> Menu {
+        Menu {
+            items: items.iter().map(|s| s.to_string()).collect(),
+            selected: 0,
+        }
+    }
+
+    pub fn select(&mut self, index: usize) {
+        self.selected = index.min(self.items.len() - 1);
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// the index of the item picked this frame
+    pub fn update(&mut self, input: &Input) -> Option<usize> {
+        if input.left.down(true) {
+            self.selected = (self.selected + self.items.len() - 1) % self.items.len();
+        }
+        if input.right.down(true) {
+            self.selected = (self.selected + 1) % self.items.len();
+        }
+        if input.down.down(true) {
+            Some(self.selected)
+        } else {
+            None
+        }
+    }
+
+    pub fn draw(&self, ctx: &Context, cam: &mut Camera, pos: Vec2) {
+        for (i, item) in self.items.iter().enumerate() {
+            let (text, colour) = if i == self.selected {
+                (format!("> {}", item), Colour::new(255, 220, 120, 255))
+            } else {
+                (format!("  {}", item), Colour::white())
+            };
+            cam.draw_disposable_text(&ctx.assets.font, text, ITEM_SIZE,
+                                     Vec2::new(pos.x, pos.y + ITEM_SPACING * i as f64),
+                                     colour, Vec2::zero());
+        }
+    }
+}
@@ -0,0 +1,122 @@
+//! The screens of the game, kept in a stack so overlays like the pause
+//! menu can sit on top of the game without it knowing about them.
+
+use nze_game_sdl::Camera;
+
+use crate::game::Context;
+
+mod menu;
+mod title;
+mod preset_select;
+mod level_select;
+mod playing;
+mod paused;
+mod game_over;
+mod settings;
+mod recipes;
+mod day_report;
+mod shop;
+
+pub use title::Title;
+pub use preset_select::PresetSelect;
+pub use level_select::LevelSelect;
+pub use playing::Playing;
+pub use paused::Paused;
+pub use game_over::GameOver;
+pub use settings::Settings;
+pub use recipes::Recipes;
+pub use day_report::DayReport;
+pub use shop::Shop;
+
+/// cents as dollars, like `$12.50`
+fn money(cents: u64) -> String {
+    format!("${}.{:02}", cents / 100, cents % 100)
+}
+
+/// What the scene stack should do after a scene updates.
+pub enum Transition {
+    None,
+    /// put a scene on top of the current one
+    Push(Box<dyn Scene>),
+    /// remove the current scene, going back to the one below
+    Pop,
+    /// swap the current scene for another
+    Replace(Box<dyn Scene>),
+    /// remove every scene and start again from this one
+    Clear(Box<dyn Scene>),
+}
+
+pub trait Scene {
+    fn enter(&mut self, _ctx: &mut Context) {}
+
+    fn exit(&mut self, _ctx: &mut Context) {}
+
+    /// only the top scene is updated
+    fn update(&mut self, ctx: &mut Context, frame_elapsed: f64) -> Transition;
+
+    fn draw(&mut self, ctx: &mut Context, cam: &mut Camera);
+
+    /// if the scenes below should be drawn underneath this one
+    fn is_overlay(&self) -> bool {
+        false
+    }
+}
+
+pub struct SceneStack {
+    scenes: Vec<Box<dyn Scene>>,
+}
+
+impl SceneStack {
+    pub fn new(first: Box<dyn Scene>, ctx: &mut Context) -> SceneStack {
+        let mut stack = SceneStack { scenes: Vec::new() };
+        stack.push(first, ctx);
+        stack
+    }
+
+    pub fn update(&mut self, ctx: &mut Context, frame_elapsed: f64) {
+        let transition = match self.scenes.last_mut() {
+            Some(scene) => scene.update(ctx, frame_elapsed),
+            None => Transition::None,
+        };
+        self.apply(transition, ctx);
+    }
+
+    pub fn draw(&mut self, ctx: &mut Context, cam: &mut Camera) {
+        let mut first = self.scenes.len().saturating_sub(1);
+        while first > 0 && self.scenes[first].is_overlay() {
+            first -= 1;
+        }
+        for scene in self.scenes[first..].iter_mut() {
+            scene.draw(ctx, cam);
+        }
+    }
+
+    pub fn apply(&mut self, transition: Transition, ctx: &mut Context) {
+        match transition {
+            Transition::None => (),
+            Transition::Push(scene) => self.push(scene, ctx),
+            Transition::Pop => self.pop(ctx),
+            Transition::Replace(scene) => {
+                self.pop(ctx);
+                self.push(scene, ctx);
+            },
+            Transition::Clear(scene) => {
+                while !self.scenes.is_empty() {
+                    self.pop(ctx);
+                }
+                self.push(scene, ctx);
+            },
+        }
+    }
+
+    fn push(&mut self, mut scene: Box<dyn Scene>, ctx: &mut Context) {
+        scene.enter(ctx);
+        self.scenes.push(scene);
+    }
+
+    fn pop(&mut self, ctx: &mut Context) {
+        if let Some(mut scene) = self.scenes.pop() {
+            scene.exit(ctx);
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use nze_game_sdl::{Camera, Colour, geometry::{Vec2, Rect}};
+
+use crate::{game::Context, VIEW_WIDTH, VIEW_HEIGHT};
+use super::{Scene, Transition, Settings, Title, menu::Menu};
+
+const RESUME: usize = 0;
+const SETTINGS: usize = 1;
+const QUIT: usize = 2;
+
+pub struct Paused {
+    menu: Menu,
+}
+
+impl Paused {
+    pub fn new() -> Paused {
+        Paused { menu: Menu::new(&["Resume", "Settings", "Quit To Title"]) }
+    }
+}
+
+impl Scene for Paused {
+    fn update(&mut self, ctx: &mut Context, _frame_elapsed: f64) -> Transition {
+        if ctx.input.pause.down(true) || ctx.input.up.down(true) {
+            return Transition::Pop;
+        }
+        match self.menu.update(&ctx.input) {
+            Some(RESUME) => Transition::Pop,
+            Some(SETTINGS) => Transition::Push(Box::new(Settings::new(ctx))),
+            Some(QUIT) => Transition::Clear(Box::new(Title::new())),
+            _ => Transition::None,
+        }
+    }
+
+    fn draw(&mut self, ctx: &mut Context, cam: &mut Camera) {
+        cam.draw_rect(Rect::new(0.0, 0.0, VIEW_WIDTH, VIEW_HEIGHT),
+                      Colour::new(0, 0, 0, 100), Vec2::zero());
+        cam.draw_disposable_text(&ctx.assets.font, "Pawsed".to_string(), TITLE_SIZE,
+                                 Vec2::new(100.0, 80.0), Colour::white(), Vec2::zero());
+        self.menu.draw(ctx, cam, Vec2::new(150.0, 200.0));
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
+
+const TITLE_SIZE: u32 = 80;
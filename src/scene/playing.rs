@@ -0,0 +1,138 @@
+use nze_game_sdl::{Camera, Colour, geometry::Vec2};
+
+use crate::{
+    game::Context,
+    sim::Action,
+    event::GameEvent,
+    timestep::TICK,
+};
+use super::{Scene, Transition, Paused, GameOver, Recipes, DayReport, money};
+
+/// The shift itself, runs the simulation in fixed ticks.
+pub struct Playing {
+    /// presses waiting for the next tick, so none are lost on fast frames
+    pending_actions: Vec<Action>,
+    /// seconds left showing that a streak was lost
+    streak_lost: f64,
+}
+
+impl Playing {
+    pub fn new() -> Playing {
+        Playing { pending_actions: Vec::new(), streak_lost: 0.0 }
+    }
+
+    /// the restaurant and hud, also drawn under overlays
+    pub fn draw_world(ctx: &mut Context, cam: &mut Camera) {
+        let assets = &mut ctx.assets;
+        cam.draw(&assets.bg);
+        for i in 0..ctx.sim.max_lives() {
+            let mut h = if i < ctx.sim.lives() {
+                assets.heart.clone()
+            } else {
+                assets.heart_off.clone()
+            };
+            h.rect.x = LIVES_POS.x + ((h.rect.w + LIVES_BUFFER) * i as f64);
+            h.rect.y = LIVES_POS.y;
+            cam.draw(&h);
+        }
+        let streak_pos = Vec2::new(
+            LIVES_POS.x + (assets.heart.rect.w + LIVES_BUFFER) * ctx.sim.max_lives() as f64,
+            LIVES_POS.y);
+        cam.draw_disposable_text(&assets.font,
+                                 format!("x{} ({})", ctx.sim.multiplier(), ctx.sim.streak()),
+                                 STREAK_SIZE, streak_pos,
+                                 Colour::new(110, 77, 36, 255), Vec2::new(1.0, 1.0));
+        cam.draw_rect(assets.bg.rect, Colour::new(0, 0, 0, BG_OPACITY), Vec2::zero());
+        let score = match &ctx.sim.rules().level {
+            Some(level) => format!("Customers: {}/{}", ctx.sim.served(), level.goal),
+            None => format!("Score: {}", ctx.sim.score()),
+        };
+        cam.draw_disposable_text(&assets.font, score,
+                                 SCORE_SIZE, SCORE_POS,
+                                 Colour::new(110, 77, 36, 255), Vec2::new(1.0, 1.0));
+        cam.draw_disposable_text(&assets.font, money(ctx.sim.cash()), EARNINGS_SIZE, EARNINGS_POS,
+                                 Colour::new(110, 77, 36, 255), Vec2::new(1.0, 1.0));
+        if let Some(left) = ctx.sim.day_time_left() {
+            let clock = if left > 0.0 {
+                let secs = left.ceil() as u64;
+                format!("Day {} - {}:{:02}", ctx.sim.day(), secs / 60, secs % 60)
+            } else {
+                format!("Day {} - Closing", ctx.sim.day())
+            };
+            cam.draw_disposable_text(&assets.font, clock, EARNINGS_SIZE, DAY_POS,
+                                     Colour::new(110, 77, 36, 255), Vec2::new(1.0, 1.0));
+        }
+
+        let alpha = ctx.clock.alpha();
+        assets.sandwitch_render.draw(cam, ctx.sim.machine(), alpha);
+        assets.customer_render.draw(cam, &ctx.sim, &assets.sandwitch_render, &assets.font, alpha);
+    }
+}
+
+impl Scene for Playing {
+    fn enter(&mut self, ctx: &mut Context) {
+        ctx.in_game = true;
+    }
+
+    fn exit(&mut self, ctx: &mut Context) {
+        ctx.in_game = false;
+    }
+
+    fn update(&mut self, ctx: &mut Context, frame_elapsed: f64) -> Transition {
+        let input = &ctx.input;
+        if input.pause.down(true) {
+            return Transition::Push(Box::new(Paused::new()));
+        }
+        if ctx.sim.is_day_over() {
+            return Transition::Push(Box::new(DayReport::new()));
+        }
+        if input.recipes.down(true) && !ctx.sim.rules().recipes.is_empty() {
+            return Transition::Push(Box::new(Recipes::new()));
+        }
+        self.pending_actions.extend(input.frame(frame_elapsed).actions());
+        self.streak_lost = (self.streak_lost - frame_elapsed).max(0.0);
+        for _ in 0..ctx.clock.advance(frame_elapsed) {
+            ctx.sim.step(TICK, &self.pending_actions);
+            self.pending_actions.clear();
+            if ctx.sim.events().iter().any(|e| matches!(e, GameEvent::StreakBroken { .. })) {
+                self.streak_lost = STREAK_LOST_TIME;
+            }
+            ctx.events.extend_from_slice(ctx.sim.events());
+            if ctx.sim.is_over() {
+                return Transition::Push(Box::new(GameOver::new()));
+            }
+            if ctx.sim.is_day_over() {
+                return Transition::Push(Box::new(DayReport::new()));
+            }
+        }
+        Transition::None
+    }
+
+    fn draw(&mut self, ctx: &mut Context, cam: &mut Camera) {
+        Self::draw_world(ctx, cam);
+        if self.streak_lost > 0.0 {
+            let alpha = (255.0 * (self.streak_lost / STREAK_LOST_TIME).min(1.0)) as u8;
+            cam.draw_disposable_text(&ctx.assets.font, "Streak Lost!".to_string(),
+                                     STREAK_SIZE, STREAK_LOST_POS,
+                                     Colour::new(200, 40, 40, alpha), Vec2::new(1.0, 1.0));
+        }
+    }
+}
+
+const SCORE_POS: Vec2 = Vec2::new(120.0, 80.0);
+const SCORE_SIZE: u32 = 40;
+
+const EARNINGS_POS: Vec2 = Vec2::new(SCORE_POS.x, SCORE_POS.y + 42.0);
+const EARNINGS_SIZE: u32 = 20;
+
+const DAY_POS: Vec2 = Vec2::new(SCORE_POS.x, EARNINGS_POS.y + 26.0);
+
+const LIVES_RECT: Vec2 = Vec2::new(10.0, 10.0);
+const LIVES_BUFFER: f64 = 10.0;
+const LIVES_POS: Vec2 = Vec2::new(SCORE_POS.x + 80.0, SCORE_POS.y + LIVES_RECT.y * 4.0);
+
+const STREAK_SIZE: u32 = 20;
+const STREAK_LOST_POS: Vec2 = Vec2::new(LIVES_POS.x, LIVES_POS.y + 30.0);
+const STREAK_LOST_TIME: f64 = 1.2;
+
+const BG_OPACITY: u8 = 64;
@@ -0,0 +1,53 @@
+use nze_game_sdl::{Camera, Colour, geometry::Vec2};
+
+use crate::game::Context;
+use super::{Scene, Transition, Title, Playing, menu::Menu};
+
+/// Picks the difficulty preset before a shift starts.
+pub struct PresetSelect {
+    menu: Menu,
+}
+
+impl PresetSelect {
+    pub fn new(ctx: &Context) -> PresetSelect {
+        let names: Vec<&str> = ctx.data.presets.iter().map(|p| p.name.as_str()).collect();
+        let mut menu = Menu::new(&names);
+        // start on the preset played last
+        if let Some(i) = ctx.data.presets.iter().position(|p| p.id == ctx.rules.preset.id) {
+            menu.select(i);
+        }
+        PresetSelect { menu }
+    }
+}
+
+impl Scene for PresetSelect {
+    fn update(&mut self, ctx: &mut Context, _frame_elapsed: f64) -> Transition {
+        if ctx.input.pause.down(true) || ctx.input.up.down(true) {
+            return Transition::Replace(Box::new(Title::new()));
+        }
+        match self.menu.update(&ctx.input) {
+            Some(i) => {
+                ctx.rules = ctx.data.rules(&ctx.data.presets[i].id).expect("preset is in the data");
+                ctx.new_game();
+                Transition::Replace(Box::new(Playing::new()))
+            },
+            None => Transition::None,
+        }
+    }
+
+    fn draw(&mut self, ctx: &mut Context, cam: &mut Camera) {
+        cam.draw(&ctx.assets.bg);
+        cam.draw_rect(ctx.assets.bg.rect, Colour::new(0, 0, 0, 150), Vec2::zero());
+        cam.draw_disposable_text(&ctx.assets.font, "Difficulty".to_string(), TITLE_SIZE,
+                                 Vec2::new(100.0, 40.0), Colour::white(), Vec2::zero());
+        self.menu.draw(ctx, cam, Vec2::new(160.0, 130.0));
+        let preset = &ctx.data.presets[self.menu.selected()];
+        cam.draw_disposable_text(&ctx.assets.font, preset.description.clone(), HINT_SIZE,
+                                 Vec2::new(80.0, 290.0), Colour::new(255, 220, 120, 255), Vec2::zero());
+        cam.draw_disposable_text(&ctx.assets.font, "Down To Start, Up To Go Back".to_string(),
+                                 HINT_SIZE, Vec2::new(80.0, 320.0), Colour::white(), Vec2::zero());
+    }
+}
+
+const TITLE_SIZE: u32 = 50;
+const HINT_SIZE: u32 = 16;
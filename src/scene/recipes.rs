@@ -0,0 +1,63 @@
+use nze_game_sdl::{Camera, Colour, geometry::{Vec2, Rect}};
+
+use crate::{game::Context, sandwitch::ING_SIZE, VIEW_WIDTH, VIEW_HEIGHT};
+use super::{Scene, Transition};
+
+/// Lists the stacks of the recipes customers can order, the game waits underneath.
+pub struct Recipes;
+
+impl Recipes {
+    pub fn new() -> Recipes {
+        Recipes
+    }
+}
+
+impl Scene for Recipes {
+    fn update(&mut self, ctx: &mut Context, _frame_elapsed: f64) -> Transition {
+        let input = &ctx.input;
+        if input.recipes.down(true) || input.pause.down(true) || input.up.down(true) {
+            return Transition::Pop;
+        }
+        Transition::None
+    }
+
+    fn draw(&mut self, ctx: &mut Context, cam: &mut Camera) {
+        cam.draw_rect(Rect::new(0.0, 0.0, VIEW_WIDTH, VIEW_HEIGHT),
+                      Colour::new(0, 0, 0, 200), Vec2::zero());
+        let assets = &ctx.assets;
+        cam.draw_disposable_text(&assets.font, "Recipes".to_string(), TITLE_SIZE,
+                                 Vec2::new(150.0, 10.0), Colour::white(), Vec2::zero());
+        for (i, (_, recipe)) in ctx.sim.rules().recipes.iter().enumerate() {
+            let cell = Vec2::new(GRID_POS.x + CELL_SIZE.x * (i % COLUMNS) as f64,
+                                 GRID_POS.y + CELL_SIZE.y * (i / COLUMNS) as f64);
+            // stacked up from the bottom of the cell, above the name
+            let bottom = cell.y + CELL_SIZE.y - NAME_HEIGHT - ING_SIZE.y;
+            assets.sandwitch_render.render_ings(cam, recipe.stack.iter(),
+                                                Vec2::new(cell.x + (CELL_SIZE.x - ING_SIZE.x) / 2.0, bottom),
+                                                ING_SIZE.into(),
+                                                -ING_SIZE.y * 0.5, -1.0);
+            if let Some(label) = recipe.rule.label(&ctx.sim.rules().ingredients) {
+                cam.draw_disposable_text(&assets.font, label, LABEL_SIZE,
+                                         Vec2::new(cell.x + 4.0, cell.y + CELL_SIZE.y - NAME_HEIGHT + NAME_SIZE as f64),
+                                         Colour::new(255, 220, 120, 255), Vec2::zero());
+            }
+            cam.draw_disposable_text(&assets.font, recipe.name.clone(), NAME_SIZE,
+                                     Vec2::new(cell.x + 4.0, cell.y + CELL_SIZE.y - NAME_HEIGHT),
+                                     Colour::white(), Vec2::zero());
+        }
+        cam.draw_disposable_text(&assets.font, "R To Close".to_string(), NAME_SIZE,
+                                 Vec2::new(190.0, VIEW_HEIGHT - 25.0), Colour::white(), Vec2::zero());
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
+
+const TITLE_SIZE: u32 = 40;
+const NAME_SIZE: u32 = 14;
+const LABEL_SIZE: u32 = 10;
+const NAME_HEIGHT: f64 = 24.0;
+const COLUMNS: usize = 4;
+const GRID_POS: Vec2 = Vec2::new(20.0, 60.0);
+const CELL_SIZE: Vec2 = Vec2::new(110.0, 130.0);
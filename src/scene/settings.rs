@@ -0,0 +1,60 @@
+use nze_game_sdl::{Camera, Colour, geometry::{Vec2, Rect}};
+
+use crate::{game::Context, VIEW_WIDTH, VIEW_HEIGHT};
+use super::{Scene, Transition, menu::Menu};
+
+const FULLSCREEN: usize = 0;
+const MANUAL_SERVING: usize = 1;
+const BACK: usize = 2;
+
+pub struct Settings {
+    menu: Menu,
+}
+
+impl Settings {
+    pub fn new(ctx: &Context) -> Settings {
+        Settings { menu: Self::menu(ctx) }
+    }
+
+    fn menu(ctx: &Context) -> Menu {
+        let serving = if ctx.manual_serving { "Serving: Manual" } else { "Serving: Automatic" };
+        Menu::new(&["Toggle Fullscreen", serving, "Back"])
+    }
+}
+
+impl Scene for Settings {
+    fn update(&mut self, ctx: &mut Context, _frame_elapsed: f64) -> Transition {
+        if ctx.input.pause.down(true) || ctx.input.up.down(true) {
+            return Transition::Pop;
+        }
+        match self.menu.update(&ctx.input) {
+            Some(FULLSCREEN) => {
+                ctx.fullscreen_requested = true;
+                Transition::None
+            },
+            Some(MANUAL_SERVING) => {
+                ctx.manual_serving = !ctx.manual_serving;
+                ctx.sim.set_manual_serving(ctx.manual_serving);
+                self.menu = Self::menu(ctx);
+                self.menu.select(MANUAL_SERVING);
+                Transition::None
+            },
+            Some(BACK) => Transition::Pop,
+            _ => Transition::None,
+        }
+    }
+
+    fn draw(&mut self, ctx: &mut Context, cam: &mut Camera) {
+        cam.draw_rect(Rect::new(0.0, 0.0, VIEW_WIDTH, VIEW_HEIGHT),
+                      Colour::new(0, 0, 0, 200), Vec2::zero());
+        cam.draw_disposable_text(&ctx.assets.font, "Settings".to_string(), TITLE_SIZE,
+                                 Vec2::new(100.0, 60.0), Colour::white(), Vec2::zero());
+        self.menu.draw(ctx, cam, Vec2::new(120.0, 160.0));
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
+
+const TITLE_SIZE: u32 = 60;
@@ -0,0 +1,75 @@
+use nze_game_sdl::{Camera, Colour, geometry::{Vec2, Rect}};
+
+use crate::{game::Context, upgrade::Upgrade, VIEW_WIDTH, VIEW_HEIGHT};
+use super::{Scene, Transition, menu::Menu, money};
+
+/// Spend the day's earnings on upgrades before the next day starts,
+/// the last item opens up.
+pub struct Shop {
+    menu: Menu,
+}
+
+impl Shop {
+    pub fn new(ctx: &Context) -> Shop {
+        Shop { menu: Self::menu(ctx) }
+    }
+
+    fn menu(ctx: &Context) -> Menu {
+        let mut items: Vec<String> = ctx.sim.rules().upgrades.iter().map(|(u, def)| {
+            format!("{} {} ({}/{})", def.name, money(def.cost), ctx.sim.owned(u), def.max)
+        }).collect();
+        items.push(format!("Open For Day {}", ctx.sim.day() + 1));
+        Menu::new(&items.iter().map(String::as_str).collect::<Vec<_>>())
+    }
+}
+
+impl Scene for Shop {
+    fn update(&mut self, ctx: &mut Context, _frame_elapsed: f64) -> Transition {
+        let upgrades = ctx.sim.rules().upgrades.len();
+        match self.menu.update(&ctx.input) {
+            Some(i) if i < upgrades => {
+                if ctx.sim.buy(Upgrade(i)) {
+                    self.menu = Self::menu(ctx);
+                    self.menu.select(i);
+                }
+                Transition::None
+            },
+            Some(_) => {
+                ctx.sim.next_day();
+                Transition::Pop
+            },
+            None => Transition::None,
+        }
+    }
+
+    fn draw(&mut self, ctx: &mut Context, cam: &mut Camera) {
+        cam.draw_rect(Rect::new(0.0, 0.0, VIEW_WIDTH, VIEW_HEIGHT),
+                      Colour::new(0, 0, 0, 160), Vec2::zero());
+        let font = &ctx.assets.font;
+        cam.draw_disposable_text(font, "Shop".to_string(), TITLE_SIZE,
+                                 Vec2::new(100.0, 30.0), Colour::white(), Vec2::zero());
+        cam.draw_disposable_text(font, format!("Cash: {}", money(ctx.sim.cash())), TEXT_SIZE,
+                                 Vec2::new(150.0, 100.0), Colour::white(), Vec2::zero());
+        let selected = self.menu.selected();
+        let upgrades = &ctx.sim.rules().upgrades;
+        if selected < upgrades.len() {
+            let upgrade = Upgrade(selected);
+            let def = upgrades.get(upgrade);
+            let colour = if ctx.sim.can_buy(upgrade) {
+                Colour::white()
+            } else {
+                Colour::new(200, 120, 120, 255)
+            };
+            cam.draw_disposable_text(font, def.description.clone(), TEXT_SIZE,
+                                     Vec2::new(150.0, 125.0), colour, Vec2::zero());
+        }
+        self.menu.draw(ctx, cam, Vec2::new(150.0, 165.0));
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
+
+const TITLE_SIZE: u32 = 50;
+const TEXT_SIZE: u32 = 20;
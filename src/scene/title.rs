@@ -0,0 +1,42 @@
+use nze_game_sdl::{Camera, Colour, geometry::Vec2};
+
+use crate::game::Context;
+use super::{Scene, Transition, PresetSelect, LevelSelect, Settings, menu::Menu};
+
+const ENDLESS: usize = 0;
+const LEVELS: usize = 1;
+const SETTINGS: usize = 2;
+
+pub struct Title {
+    menu: Menu,
+}
+
+impl Title {
+    pub fn new() -> Title {
+        Title { menu: Menu::new(&["Endless", "Levels", "Settings"]) }
+    }
+}
+
+impl Scene for Title {
+    fn update(&mut self, ctx: &mut Context, _frame_elapsed: f64) -> Transition {
+        match self.menu.update(&ctx.input) {
+            Some(ENDLESS) => Transition::Replace(Box::new(PresetSelect::new(ctx))),
+            Some(LEVELS) => Transition::Replace(Box::new(LevelSelect::new(ctx))),
+            Some(SETTINGS) => Transition::Push(Box::new(Settings::new(ctx))),
+            _ => Transition::None,
+        }
+    }
+
+    fn draw(&mut self, ctx: &mut Context, cam: &mut Camera) {
+        cam.draw(&ctx.assets.bg);
+        cam.draw_rect(ctx.assets.bg.rect, Colour::new(0, 0, 0, 150), Vec2::zero());
+        cam.draw_disposable_text(&ctx.assets.font, "Deli Cat Essen".to_string(), TITLE_SIZE,
+                                 Vec2::new(60.0, 60.0), Colour::white(), Vec2::zero());
+        self.menu.draw(ctx, cam, Vec2::new(180.0, 160.0));
+        cam.draw_disposable_text(&ctx.assets.font, "Left/Right To Choose, Down To Select".to_string(),
+                                 HINT_SIZE, Vec2::new(80.0, 320.0), Colour::white(), Vec2::zero());
+    }
+}
+
+const TITLE_SIZE: u32 = 60;
+const HINT_SIZE: u32 = 16;
@@ -0,0 +1,558 @@
+//! The game logic without any rendering or SDL input,
+//! so full games can be run headless.
+
+use std::{fs::File, io::{self, BufReader, BufWriter}, path::Path};
+
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    customer::CustomerLine,
+    sandwitch::SandwitchMachine,
+    event::{GameEvent, Stats},
+    rules::Rules,
+    upgrade::{Upgrade, Effect},
+    ingredient::Ingredient,
+    generator::QueueGenerator,
+};
+
+/// The rng used by the game logic, unlike `StdRng` its output
+/// is the same on every platform and version, so a seed always gives the same game.
+pub type GameRng = ChaCha8Rng;
+
+/// Something the player can do to the sandwitch machine in a step.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Action {
+    /// move the chef along the plates, negative is to the left
+    Switch(i32),
+    /// drop the next ingredient in the queue onto the active plate
+    Release,
+    /// send the top ingredient of the active plate back to the queue
+    Bin,
+    /// give the active plate to the customer whose order it is closest to,
+    /// or to the selected customer when serving manually
+    Serve,
+    /// move the customer selection, negative is to the left
+    SelectCustomer(i32),
+    /// put the next ingredient aside, or swap it with the one already put aside
+    Hold,
+    /// rewind the latest release, bin, switch or hold, for the preset's `undo_cost`
+    Undo,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Simulation {
+    rules: Rules,
+    customer_line: CustomerLine,
+    machine: SandwitchMachine,
+    /// the day of the run, from 1
+    day: u32,
+    /// seconds since the day started
+    day_time: f64,
+    /// totals of the day's events, for the end of day report
+    day_stats: Stats,
+    /// cents spent on upgrades
+    spent: u64,
+    /// times each upgrade has been bought, indexed by `Upgrade`
+    owned: Vec<u32>,
+    /// upgrades bought since the day ended, applied when the next one starts
+    bought: Vec<Upgrade>,
+    /// hearts from upgrades, on top of the preset's lives
+    extra_lives: u32,
+    /// multiplies the chef's speed
+    chef_speed: f64,
+    /// plates are only served by hand to the selected customer, never automatically
+    manual_serving: bool,
+    #[serde(skip)]
+    events: Vec<GameEvent>,
+}
+
+impl Default for Simulation {
+    fn default() -> Simulation {
+        Simulation::new()
+    }
+}
+
+impl Simulation {
+    /// a game with a random seed
+    pub fn new() -> Simulation {
+        Self::from_rng(&mut thread_rng())
+    }
+
+    /// the same seed always gives the same customer orders and ingredient queue
+    pub fn from_seed(seed: u64) -> Simulation {
+        Self::from_rng(&mut GameRng::seed_from_u64(seed))
+    }
+
+    /// seed the game from any rng, using the built in rules
+    pub fn from_rng<R: Rng + ?Sized>(rng: &mut R) -> Simulation {
+        Self::with_rules(rng, Rules::builtin())
+    }
+
+    pub fn with_rules<R: Rng + ?Sized>(rng: &mut R, rules: Rules) -> Simulation {
+        Simulation {
+            customer_line: CustomerLine::new(GameRng::seed_from_u64(rng.gen()), &rules),
+            machine: SandwitchMachine::new(GameRng::seed_from_u64(rng.gen()), &rules.ingredients,
+                                           rules.preset.queue_size, rules.queue_generator().clone()),
+            owned: vec![0; rules.upgrades.len()],
+            rules,
+            day: 1,
+            day_time: 0.0,
+            day_stats: Stats::new(),
+            spent: 0,
+            bought: Vec::new(),
+            extra_lives: 0,
+            chef_speed: 1.0,
+            manual_serving: false,
+            events: Vec::new(),
+        }
+    }
+
+    /// advance the game by `dt` seconds, then apply `actions` in order,
+    /// nothing happens once the day is over until the next one starts
+    pub fn step(&mut self, dt: f64, actions: &[Action]) {
+        self.events.clear();
+        if self.is_day_over() {
+            return;
+        }
+        self.day_time += dt;
+        if self.day_length().is_some_and(|length| self.day_time >= length) {
+            self.customer_line.close();
+        }
+        self.customer_line.update(dt, &self.rules);
+        self.collect_events();
+        self.machine.update(dt);
+        if !self.manual_serving {
+            self.customer_line.check_machine(&mut self.machine, &self.rules);
+        }
+        self.collect_events();
+        if matches!(self.rules.queue_generator(), QueueGenerator::Demand(_)) {
+            let demand = self.demand();
+            self.machine.set_demand(demand);
+        }
+        for action in actions {
+            match action {
+                Action::Switch(diff) => self.machine.switch(*diff),
+                Action::Release => self.machine.release(&self.rules.ingredients),
+                Action::Bin => self.machine.bin(),
+                Action::Serve if self.manual_serving =>
+                    self.customer_line.deliver_plate(&mut self.machine, &self.rules),
+                Action::Serve => self.customer_line.serve_plate(&mut self.machine, &self.rules),
+                Action::SelectCustomer(diff) => self.customer_line.select_customer(*diff),
+                Action::Hold => self.machine.hold(&self.rules.ingredients),
+                Action::Undo => {
+                    if self.machine.undo() {
+                        self.customer_line.lose_points(self.rules.preset.undo_cost);
+                    }
+                },
+            }
+            self.collect_events();
+        }
+        if self.is_day_over() {
+            self.events.push(GameEvent::DayEnded { day: self.day });
+        }
+        for e in &self.events {
+            self.day_stats.record(e);
+        }
+    }
+
+    /// seconds in a day, none in a level or if the preset has no days
+    pub fn day_length(&self) -> Option<f64> {
+        match self.rules.level {
+            Some(_) => None,
+            None if self.rules.preset.day_length > 0.0 => Some(self.rules.preset.day_length),
+            None => None,
+        }
+    }
+
+    pub fn set_manual_serving(&mut self, manual: bool) {
+        self.manual_serving = manual;
+    }
+
+    pub fn manual_serving(&self) -> bool {
+        self.manual_serving
+    }
+
+    /// if the day's time is up and the last customer has left
+    pub fn is_day_over(&self) -> bool {
+        self.customer_line.is_closed() && self.customer_line.is_empty()
+    }
+
+    /// open for the next day, a little harder than the last,
+    /// does nothing until the day is over
+    pub fn next_day(&mut self) {
+        if !self.is_day_over() {
+            return;
+        }
+        for upgrade in std::mem::take(&mut self.bought) {
+            self.apply(upgrade);
+        }
+        self.day += 1;
+        self.day_time = 0.0;
+        self.day_stats = Stats::new();
+        self.customer_line.open(self.rules.preset.day_step * (self.day - 1) as f64,
+                                &self.rules.preset.difficulty);
+    }
+
+    /// if the upgrade can be bought now, only between days
+    pub fn can_buy(&self, upgrade: Upgrade) -> bool {
+        let def = self.rules.upgrades.get(upgrade);
+        self.is_day_over() && self.owned(upgrade) < def.max && self.cash() >= def.cost
+    }
+
+    /// pay for an upgrade to start with the next day, false if it can't be bought
+    pub fn buy(&mut self, upgrade: Upgrade) -> bool {
+        if !self.can_buy(upgrade) {
+            return false;
+        }
+        self.spent += self.rules.upgrades.get(upgrade).cost;
+        self.owned[upgrade.0] += 1;
+        self.bought.push(upgrade);
+        true
+    }
+
+    fn apply(&mut self, upgrade: Upgrade) {
+        match self.rules.upgrades.get(upgrade).effect {
+            Effect::Plates(n) => self.machine.add_plates(n, &self.rules.ingredients),
+            Effect::QueueSize(n) => self.machine.grow_queue(n, &self.rules.ingredients),
+            Effect::Patience(x) => self.customer_line.add_patience(x),
+            Effect::Lives(n) => {
+                self.extra_lives += n;
+                self.customer_line.add_lives(n);
+            },
+            Effect::ChefSpeed(x) => {
+                self.chef_speed += x;
+                self.machine.set_chef_speed(self.chef_speed);
+            },
+        }
+    }
+
+    /// times the upgrade has been bought
+    pub fn owned(&self, upgrade: Upgrade) -> u32 {
+        self.owned[upgrade.0]
+    }
+
+    /// cents earned and not spent on upgrades
+    pub fn cash(&self) -> u64 {
+        self.earnings() - self.spent
+    }
+
+    /// the preset's lives and any extra hearts bought
+    pub fn max_lives(&self) -> u32 {
+        self.rules.preset.lives + self.extra_lives
+    }
+
+    pub fn day(&self) -> u32 {
+        self.day
+    }
+
+    /// seconds until closing time, none if there are no days
+    pub fn day_time_left(&self) -> Option<f64> {
+        self.day_length().map(|length| (length - self.day_time).max(0.0))
+    }
+
+    /// totals of today's events
+    pub fn day_stats(&self) -> &Stats {
+        &self.day_stats
+    }
+
+    /// the ingredients of the waiting customers' orders that aren't on a plate or in the queue yet
+    fn demand(&self) -> Vec<Ingredient> {
+        let mut have: Vec<Ingredient> = self.machine.plates().iter().skip(1)
+            .chain([self.machine.queue(), &self.machine.hold])
+            .flat_map(|sw| sw.ingredients.iter().copied())
+            .collect();
+        let line = &self.customer_line;
+        let mut demand = Vec::new();
+        for c in line.customers().iter().take(line.active_customers()).filter(|c| c.waiting()) {
+            let order: Vec<Ingredient> = c.order().iter().copied().collect();
+            for ing in c.rule().example(&order, &self.rules.ingredients) {
+                match have.iter().position(|h| *h == ing) {
+                    Some(i) => { have.swap_remove(i); },
+                    None => demand.push(ing),
+                }
+            }
+        }
+        demand
+    }
+
+    fn collect_events(&mut self) {
+        let line_events = self.customer_line.take_events();
+        if line_events.iter().any(|e| matches!(e, GameEvent::OrderServed { .. })) {
+            self.machine.clear_history();
+        }
+        self.events.extend(line_events);
+        let machine_events = self.machine.take_events();
+        if machine_events.iter().any(|e| matches!(e, GameEvent::IngredientBinned { .. })) {
+            self.customer_line.break_streak();
+        }
+        self.events.extend(machine_events);
+        self.events.append(&mut self.customer_line.take_events());
+    }
+
+    /// what happened during the last step, in order
+    pub fn events(&self) -> &[GameEvent] {
+        &self.events
+    }
+
+    /// write the whole game state, including the rngs, so it can be resumed later
+    pub fn save(&self, path: &Path) -> Result<(), io::Error> {
+        serde_json::to_writer(BufWriter::new(File::create(path)?), self)?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Simulation, io::Error> {
+        Ok(serde_json::from_reader(BufReader::new(File::open(path)?))?)
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.customer_line.lives() == 0 || self.is_won()
+            || self.customer_line.script_finished(&self.rules)
+    }
+
+    /// if the level's goal has been reached, never in endless mode
+    pub fn is_won(&self) -> bool {
+        self.rules.level.as_ref().is_some_and(|l| self.served() >= l.goal)
+    }
+
+    pub fn score(&self) -> u64 {
+        self.customer_line.get_score()
+    }
+
+    /// cents taken, including tips
+    pub fn earnings(&self) -> u64 {
+        self.customer_line.earnings()
+    }
+
+    pub fn tips(&self) -> u64 {
+        self.customer_line.tips()
+    }
+
+    pub fn served(&self) -> u64 {
+        self.customer_line.served()
+    }
+
+    /// serves in a row without an angry customer or a binned ingredient
+    pub fn streak(&self) -> u32 {
+        self.customer_line.streak()
+    }
+
+    /// what the points of the next serve are multiplied by
+    pub fn multiplier(&self) -> f64 {
+        self.customer_line.multiplier()
+    }
+
+    pub fn lives(&self) -> u32 {
+        self.customer_line.lives()
+    }
+
+    /// the rules the game is played with, saved along with it
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    pub fn customer_line(&self) -> &CustomerLine {
+        &self.customer_line
+    }
+
+    pub fn machine(&self) -> &SandwitchMachine {
+        &self.machine
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{moving_target::Target, sandwitch::Sandwitch, timestep::TICK};
+
+    /// step until the first customer is waiting at the counter
+    fn first_customer_waiting(sim: &mut Simulation) {
+        for _ in 0..(60.0 / TICK) as usize {
+            if sim.customer_line.customers().first().is_some_and(|c| c.waiting()) {
+                return;
+            }
+            sim.step(TICK, &[]);
+        }
+        panic!("no customer came");
+    }
+
+    /// put a plate meeting the first customer's order on plate 1, with the chef over it
+    fn plate_for_first_customer(sim: &mut Simulation) {
+        let c = &sim.customer_line.customers()[0];
+        let order: Vec<Ingredient> = c.order().iter().copied().collect();
+        let mut sw = Sandwitch::new();
+        for ing in c.rule().example(&order, &sim.rules.ingredients) {
+            sw.add_back(ing, Target::new());
+        }
+        sim.machine.sandwitches[1] = sw;
+        sim.step(TICK, &[Action::Switch(1 - sim.machine.active() as i32)]);
+    }
+
+    fn served_after_a_while(manual: bool) -> u64 {
+        let mut sim = Simulation::from_seed(0);
+        sim.set_manual_serving(manual);
+        first_customer_waiting(&mut sim);
+        plate_for_first_customer(&mut sim);
+        for _ in 0..(2.0 / TICK) as usize {
+            sim.step(TICK, &[]);
+        }
+        sim.served()
+    }
+
+    #[test]
+    fn finished_plates_are_only_taken_when_not_serving_manually() {
+        assert_eq!(served_after_a_while(false), 1);
+        assert_eq!(served_after_a_while(true), 0);
+    }
+
+    #[test]
+    fn serving_manually_hands_the_plate_over() {
+        let mut sim = Simulation::from_seed(0);
+        sim.set_manual_serving(true);
+        first_customer_waiting(&mut sim);
+        plate_for_first_customer(&mut sim);
+        sim.step(TICK, &[Action::SelectCustomer(-(sim.customer_line.selected() as i32)), Action::Serve]);
+        assert_eq!(sim.served(), 1);
+    }
+
+    /// end the day straight away, as if the last customer just left
+    fn close_day(sim: &mut Simulation) {
+        sim.customer_line.close();
+        sim.customer_line.customers.clear();
+        sim.customer_line.leaving_customers.clear();
+        sim.customer_line.angry_customers.clear();
+        assert!(sim.is_day_over());
+    }
+
+    fn upgrade(sim: &Simulation, id: &str) -> Upgrade {
+        sim.rules.upgrades.find(id).unwrap()
+    }
+
+    #[test]
+    fn upgrades_cost_what_is_left_after_spending() {
+        let mut sim = Simulation::from_seed(0);
+        close_day(&mut sim);
+        let plate = upgrade(&sim, "extra_plate");
+        let cost = sim.rules.upgrades.get(plate).cost;
+        sim.customer_line.earnings = cost * 3 / 2;
+        assert!(sim.buy(plate));
+        assert_eq!(sim.cash(), cost / 2);
+        assert!(!sim.can_buy(plate));
+        assert!(!sim.buy(plate));
+        assert_eq!(sim.owned(plate), 1);
+
+        sim.customer_line.earnings = cost * 2;
+        assert_eq!(sim.cash(), cost);
+        assert!(sim.buy(plate));
+        assert_eq!(sim.cash(), 0);
+    }
+
+    #[test]
+    fn upgrades_stop_at_their_max() {
+        let mut sim = Simulation::from_seed(0);
+        close_day(&mut sim);
+        let queue = upgrade(&sim, "longer_queue");
+        let def = sim.rules.upgrades.get(queue).clone();
+        sim.customer_line.earnings = def.cost * (def.max as u64 + 1);
+        for _ in 0..def.max {
+            assert!(sim.buy(queue));
+        }
+        assert!(!sim.can_buy(queue));
+        assert!(!sim.buy(queue));
+        assert_eq!(sim.owned(queue), def.max);
+        assert_eq!(sim.cash(), def.cost);
+    }
+
+    #[test]
+    fn upgrades_are_only_sold_between_days() {
+        let mut sim = Simulation::from_seed(0);
+        let chairs = upgrade(&sim, "comfy_chairs");
+        sim.customer_line.earnings = 1_000_000;
+        assert!(!sim.is_day_over());
+        assert!(!sim.can_buy(chairs));
+        assert!(!sim.buy(chairs));
+        close_day(&mut sim);
+        assert!(sim.buy(chairs));
+        sim.next_day();
+        assert!(!sim.buy(chairs));
+    }
+
+    #[test]
+    fn upgrades_apply_when_the_next_day_starts() {
+        let mut sim = Simulation::from_seed(0);
+        sim.customer_line.earnings = 1_000_000;
+        close_day(&mut sim);
+        let plates = sim.machine.plates().len();
+        let queue = sim.machine.queue().ingredients.len();
+        let lives = sim.lives();
+        let speed = sim.machine.queue().target.speed;
+        for id in ["extra_plate", "longer_queue", "comfy_chairs", "extra_heart", "fast_paws"] {
+            assert!(sim.buy(upgrade(&sim, id)), "{}", id);
+        }
+        // nothing changes until the day starts
+        assert_eq!(sim.machine.plates().len(), plates);
+        assert_eq!(sim.max_lives(), sim.rules.preset.lives);
+
+        sim.next_day();
+        assert_eq!(sim.machine.plates().len(), plates + 1);
+        assert_eq!(sim.machine.queue().ingredients.len(), queue + 1);
+        assert_eq!(sim.lives(), lives + 1);
+        assert_eq!(sim.max_lives(), sim.rules.preset.lives + 1);
+        assert_eq!(sim.machine.queue().target.speed, speed * 1.25);
+        first_customer_waiting(&mut sim);
+        let c = &sim.customer_line.customers()[0];
+        let patience = sim.rules.preset.difficulty.wait_time.at(sim.rules.preset.day_step)
+            * sim.rules.archetypes.get(c.archetype).patience;
+        assert!((c.wait_max - patience * 1.15).abs() < 1e-9);
+    }
+
+    /// the patience the first customer would have with `progress` customers behind the line
+    fn expected_patience(sim: &Simulation, progress: f64) -> f64 {
+        let c = &sim.customer_line.customers()[0];
+        sim.rules.preset.difficulty.wait_time.at(progress) * sim.rules.archetypes.get(c.archetype).patience
+    }
+
+    #[test]
+    fn a_day_closes_then_ends_once() {
+        let mut sim = Simulation::from_seed(0);
+        // enough hearts to sit through a whole day without serving anyone
+        sim.customer_line.add_lives(100);
+        let length = sim.day_length().unwrap();
+        first_customer_waiting(&mut sim);
+        assert_eq!(sim.customer_line.customers()[0].wait_max, expected_patience(&sim, 0.0));
+
+        let mut ended = 0;
+        for _ in 0..((length + 300.0) / TICK) as usize {
+            sim.step(TICK, &[]);
+            for e in sim.events() {
+                match e {
+                    GameEvent::CustomerArrived => assert!(sim.day_time < length, "arrived after closing"),
+                    GameEvent::DayEnded { day } => {
+                        assert_eq!(*day, 1);
+                        ended += 1;
+                    },
+                    _ => (),
+                }
+            }
+        }
+        assert_eq!(ended, 1);
+        assert!(sim.is_day_over());
+        assert!(sim.day_stats().angry > 0);
+        assert!(sim.day_stats().lives_lost >= sim.day_stats().angry);
+
+        sim.next_day();
+        assert_eq!(sim.day(), 2);
+        assert!(!sim.is_day_over());
+        assert_eq!(sim.day_time_left(), Some(length));
+        assert_eq!(sim.day_stats().angry, 0);
+        assert_eq!(sim.day_stats().lives_lost, 0);
+        // nothing more happens until the day is over again
+        sim.next_day();
+        assert_eq!(sim.day(), 2);
+
+        first_customer_waiting(&mut sim);
+        assert_eq!(sim.served(), 0);
+        assert_eq!(sim.customer_line.customers()[0].wait_max, expected_patience(&sim, sim.rules.preset.day_step));
+    }
+}
@@ -0,0 +1,185 @@
+//! Plays many games headless with a bot and prints the results as json,
+//! for tuning the game balance with data instead of play testing.
+//!
+//! `simulate --games 100 --seed 0 --bot greedy --data resources/data --preset normal --difficulty curves.json`
+//! or `simulate --level 01_opening_day` to play a level instead of endless mode.
+
+use std::{collections::BTreeMap, env, path::Path, process};
+
+use rand::SeedableRng;
+use serde::Serialize;
+
+use deli_cat_essen::{
+    agent::{play, Agent, GreedyAgent},
+    preset::{Preset, DEFAULT_PRESET},
+    data::DATA_DIR,
+    cli::arg_value,
+    event::Stats,
+    rules::{Rules, GameData},
+    sim::{Simulation, GameRng},
+};
+
+#[derive(Serialize)]
+struct GameResult {
+    seed: u64,
+    score: u64,
+    served: u64,
+    /// cents taken, including tips
+    earnings: u64,
+    tips: u64,
+    lives: u32,
+    /// if the level was finished
+    won: bool,
+    /// the day the game ended on
+    day: u32,
+    /// seconds of game time played
+    time: f64,
+    /// average seconds a served customer waited
+    avg_wait: f64,
+    stats: Stats,
+}
+
+#[derive(Serialize)]
+struct Distribution {
+    mean: f64,
+    min: u64,
+    max: u64,
+    /// how many games ended with each value
+    counts: BTreeMap<u64, usize>,
+}
+
+impl Distribution {
+    fn new(values: impl Iterator<Item = u64>) -> Distribution {
+        let mut counts = BTreeMap::new();
+        let mut total = 0;
+        for v in values {
+            *counts.entry(v).or_insert(0) += 1;
+            total += v;
+        }
+        let games: usize = counts.values().sum();
+        Distribution {
+            mean: if games == 0 { 0.0 } else { total as f64 / games as f64 },
+            min: counts.keys().next().copied().unwrap_or(0),
+            max: counts.keys().next_back().copied().unwrap_or(0),
+            counts,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Report {
+    bot: String,
+    first_seed: u64,
+    games: u64,
+    action_delay: f64,
+    time_limit: f64,
+    preset: Preset,
+    level: Option<String>,
+    /// games where the level was finished
+    wins: usize,
+    score: Distribution,
+    served: Distribution,
+    earnings: Distribution,
+    lives: Distribution,
+    days: Distribution,
+    /// average seconds a served customer waited over every game
+    avg_wait: f64,
+    results: Vec<GameResult>,
+}
+
+fn parse_arg<T: std::str::FromStr>(args: &[String], name: &str, default: T) -> T {
+    match arg_value(args, name) {
+        Some(v) => v.parse().unwrap_or_else(|_| {
+            eprintln!("invalid value for {}: {}", name, v);
+            process::exit(1);
+        }),
+        None => default,
+    }
+}
+
+fn make_agent(name: &str) -> Option<Box<dyn Agent>> {
+    match name {
+        "greedy" => Some(Box::new(GreedyAgent)),
+        _ => None,
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let games: u64 = parse_arg(&args, "--games", 100);
+    let first_seed: u64 = parse_arg(&args, "--seed", 0);
+    let bot: String = parse_arg(&args, "--bot", "greedy".to_string());
+    let action_delay: f64 = parse_arg(&args, "--action-delay", 0.3);
+    let time_limit: f64 = parse_arg(&args, "--time-limit", 1800.0);
+    let data_dir: String = parse_arg(&args, "--data", DATA_DIR.to_string());
+    let data = GameData::load(Path::new(&data_dir)).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(1);
+    });
+    let preset: String = parse_arg(&args, "--preset", DEFAULT_PRESET.to_string());
+    let mut rules = match arg_value(&args, "--level") {
+        Some(level) => data.level_rules(&level).unwrap_or_else(|| {
+            let ids: Vec<&str> = data.levels.iter().map(|l| l.id.as_str()).collect();
+            eprintln!("unknown level {}, try one of {}", level, ids.join(", "));
+            process::exit(1);
+        }),
+        None => data.rules(&preset).unwrap_or_else(|| {
+            let ids: Vec<&str> = data.presets.iter().map(|p| p.id.as_str()).collect();
+            eprintln!("unknown preset {}, try one of {}", preset, ids.join(", "));
+            process::exit(1);
+        }),
+    };
+    // try out a difficulty file without replacing the one in the data folder
+    if let Some(path) = arg_value(&args, "--difficulty") {
+        rules.preset.difficulty = Rules::load_difficulty(Path::new(&path)).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            process::exit(1);
+        });
+    }
+
+    let mut results = Vec::new();
+    let mut total = Stats::new();
+    for seed in first_seed..first_seed + games {
+        let mut agent = make_agent(&bot).unwrap_or_else(|| {
+            eprintln!("unknown bot {}, try greedy", bot);
+            process::exit(1);
+        });
+        let mut sim = Simulation::with_rules(&mut GameRng::seed_from_u64(seed), rules.clone());
+        let mut stats = Stats::new();
+        let time = play(&mut sim, agent.as_mut(), action_delay, time_limit, &mut stats);
+        total.served += stats.served;
+        total.total_wait += stats.total_wait;
+        results.push(GameResult {
+            seed,
+            score: sim.score(),
+            served: sim.served(),
+            earnings: sim.earnings(),
+            tips: sim.tips(),
+            lives: sim.lives(),
+            won: sim.is_won(),
+            day: sim.day(),
+            time,
+            avg_wait: stats.average_wait(),
+            stats,
+        });
+    }
+
+    let report = Report {
+        bot,
+        first_seed,
+        games,
+        action_delay,
+        time_limit,
+        preset: rules.preset.clone(),
+        level: rules.level.as_ref().map(|l| l.id.clone()),
+        wins: results.iter().filter(|r| r.won).count(),
+        score: Distribution::new(results.iter().map(|r| r.score)),
+        served: Distribution::new(results.iter().map(|r| r.served)),
+        earnings: Distribution::new(results.iter().map(|r| r.earnings)),
+        lives: Distribution::new(results.iter().map(|r| r.lives as u64)),
+        days: Distribution::new(results.iter().map(|r| r.day as u64)),
+        avg_wait: total.average_wait(),
+        results,
+    };
+    println!("{}", serde_json::to_string_pretty(&report).expect("report is serialisable"));
+}
@@ -0,0 +1,39 @@
+//! Runs the game logic in fixed steps no matter the frame rate,
+//! so timers and movement behave the same on every machine.
+
+/// Seconds of game time simulated by each step.
+pub const TICK: f64 = 1.0 / 120.0;
+
+/// The most time one frame can add, so a long stall (like dragging the window)
+/// doesn't make the game run hundreds of steps to catch up.
+const MAX_FRAME_TIME: f64 = 0.25;
+
+pub struct FixedStep {
+    accumulator: f64,
+}
+
+impl Default for FixedStep {
+    fn default() -> FixedStep {
+        FixedStep::new()
+    }
+}
+
+impl FixedStep {
+    pub fn new() -> FixedStep {
+        FixedStep { accumulator: 0.0 }
+    }
+
+    /// add the time of a frame and return how many ticks should be run
+    pub fn advance(&mut self, frame_elapsed: f64) -> u32 {
+        self.accumulator += frame_elapsed.min(MAX_FRAME_TIME);
+        let ticks = (self.accumulator / TICK).floor();
+        self.accumulator -= ticks * TICK;
+        ticks as u32
+    }
+
+    /// how far the frame is between the last tick and the next, from 0 to 1,
+    /// used to interpolate positions when drawing
+    pub fn alpha(&self) -> f64 {
+        self.accumulator / TICK
+    }
+}
@@ -0,0 +1,91 @@
+//! Upgrades bought with earnings between days, defined in `resources/data/upgrades.json`.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::data::{load_json, DataError};
+
+/// An upgrade, the index of its definition in the `UpgradeShop`.
+#[derive(Hash, Eq, PartialEq, Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Upgrade(pub usize);
+
+/// What an upgrade does each time it is bought.
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Effect {
+    /// more plates for the chef to build on
+    Plates(usize),
+    /// more ingredients waiting to be released
+    QueueSize(usize),
+    /// added to the customers' patience multiplier
+    Patience(f64),
+    /// extra hearts, each one filled when bought
+    Lives(u32),
+    /// added to the chef's speed multiplier
+    ChefSpeed(f64),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UpgradeDef {
+    pub id: String,
+    pub name: String,
+    /// shown in the shop under the list
+    pub description: String,
+    /// cents
+    pub cost: u64,
+    /// how many times it can be bought in a run
+    pub max: u32,
+    pub effect: Effect,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct UpgradeShop {
+    defs: Vec<UpgradeDef>,
+}
+
+impl UpgradeShop {
+    pub fn new(defs: Vec<UpgradeDef>) -> Result<UpgradeShop, String> {
+        for (i, d) in defs.iter().enumerate() {
+            if defs[..i].iter().any(|other| other.id == d.id) {
+                return Err(format!("upgrade {} is defined twice", d.id));
+            }
+            let positive = match d.effect {
+                Effect::Plates(n) | Effect::QueueSize(n) => n > 0,
+                Effect::Lives(n) => n > 0,
+                Effect::Patience(x) | Effect::ChefSpeed(x) => x > 0.0,
+            };
+            if !positive {
+                return Err(format!("upgrade {} must have a positive effect", d.id));
+            }
+        }
+        Ok(UpgradeShop { defs })
+    }
+
+    pub fn load(path: &Path) -> Result<UpgradeShop, DataError> {
+        Self::new(load_json(path)?)
+            .map_err(|e| DataError::Invalid(path.display().to_string(), e))
+    }
+
+    pub fn get(&self, upgrade: Upgrade) -> &UpgradeDef {
+        &self.defs[upgrade.0]
+    }
+
+    pub fn find(&self, id: &str) -> Option<Upgrade> {
+        self.defs.iter().position(|d| d.id == id).map(Upgrade)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Upgrade, &UpgradeDef)> {
+        self.defs.iter().enumerate().map(|(i, d)| (Upgrade(i), d))
+    }
+
+    pub fn len(&self) -> usize {
+        self.defs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.defs.is_empty()
+    }
+}
@@ -0,0 +1,181 @@
+//! Full games played headless through `Simulation::step` and the bots.
+
+use rand::SeedableRng;
+
+use deli_cat_essen::{
+    agent::{play, GreedyAgent},
+    event::Stats,
+    replay::{InputFrame, Replay},
+    rules::GameData,
+    sim::{Action, GameRng, Simulation},
+    timestep::{FixedStep, TICK},
+};
+
+#[test]
+fn greedy_bot_scores_until_out_of_lives() {
+    let mut sim = Simulation::from_seed(0);
+    let mut stats = Stats::new();
+    play(&mut sim, &mut GreedyAgent, 0.5, 3600.0, &mut stats);
+    assert!(sim.is_over());
+    assert_eq!(sim.lives(), 0);
+    assert!(sim.served() > 0);
+    assert!(sim.score() > 0);
+    assert_eq!(stats.served, sim.served());
+    assert!(stats.lives_lost >= sim.rules().preset.lives as u64);
+}
+
+#[test]
+fn an_idle_game_loses_every_life() {
+    let mut sim = Simulation::from_seed(1);
+    for _ in 0..(600.0 / TICK) as usize {
+        sim.step(TICK, &[]);
+        sim.next_day();
+    }
+    // nobody is served without a player, so every customer leaves angry
+    assert!(sim.is_over());
+    assert_eq!(sim.score(), 0);
+    assert_eq!(sim.served(), 0);
+    assert_eq!(sim.lives(), 0);
+}
+
+#[test]
+fn release_drops_the_next_ingredient_onto_the_active_plate() {
+    let mut sim = Simulation::from_seed(2);
+    let bun = sim.rules().ingredients.base_bun();
+    let next = *sim.machine().queue().ingredients.back().unwrap();
+    sim.step(TICK, &[Action::Switch(1), Action::Release]);
+    assert_eq!(sim.machine().active(), 1);
+    let plate: Vec<_> = sim.machine().plates()[1].ingredients.iter().copied().collect();
+    assert_eq!(plate, vec![bun, next]);
+    assert_eq!(sim.machine().queue().ingredients.len(), sim.rules().preset.queue_size);
+
+    sim.step(TICK, &[Action::Bin]);
+    let plate: Vec<_> = sim.machine().plates()[1].ingredients.iter().copied().collect();
+    assert_eq!(plate, vec![bun]);
+    assert_eq!(*sim.machine().queue().ingredients.back().unwrap(), next);
+}
+
+#[test]
+fn switching_any_distance_stays_on_a_plate() {
+    let mut sim = Simulation::from_seed(3);
+    let plates = sim.machine().plates().len();
+    for diff in [-7, -(plates as i32), 13, -100, 100] {
+        sim.step(TICK, &[Action::Switch(diff)]);
+        assert!(sim.machine().active() < plates);
+    }
+}
+
+#[test]
+fn greedy_bot_wins_the_first_level() {
+    let rules = GameData::builtin().level_rules("01_opening_day").unwrap();
+    let mut sim = Simulation::with_rules(&mut GameRng::seed_from_u64(0), rules);
+    let mut stats = Stats::new();
+    play(&mut sim, &mut GreedyAgent, 0.2, 600.0, &mut stats);
+    assert!(sim.is_won());
+    assert!(sim.lives() > 0);
+}
+
+/// the queue, the plates and each customer's order, for comparing games
+fn snapshot(sim: &Simulation) -> Vec<Vec<usize>> {
+    let machine = sim.machine();
+    let mut state = vec![machine.queue().ingredients.iter().map(|i| i.0).collect()];
+    state.extend(machine.plates().iter().map(|p| p.ingredients.iter().map(|i| i.0).collect()));
+    state.extend(sim.customer_line().customers().iter().map(|c| c.order().iter().map(|i| i.0).collect()));
+    state
+}
+
+#[test]
+fn a_seed_always_gives_the_same_game() {
+    let mut a = Simulation::from_seed(42);
+    let mut b = Simulation::from_seed(42);
+    assert_eq!(snapshot(&a), snapshot(&b));
+    let actions = [Action::Switch(1), Action::Release, Action::Switch(1), Action::Release, Action::Bin];
+    for i in 0..(60.0 / TICK) as usize {
+        let action = &actions[i % actions.len()..][..1];
+        a.step(TICK, action);
+        b.step(TICK, action);
+        assert_eq!(snapshot(&a), snapshot(&b));
+        assert_eq!(a.events(), b.events());
+    }
+    assert_eq!(a.score(), b.score());
+    assert_eq!(a.lives(), b.lives());
+
+    let c = Simulation::from_seed(43);
+    assert_ne!(snapshot(&Simulation::from_seed(42)), snapshot(&c));
+}
+
+#[test]
+fn a_loaded_game_plays_on_like_the_saved_one() {
+    let mut a = Simulation::from_seed(7);
+    let mut stats = Stats::new();
+    play(&mut a, &mut GreedyAgent, 0.5, 30.0, &mut stats);
+    let path = std::env::temp_dir().join(format!("deli_cat_essen_save_{}.json", std::process::id()));
+    a.save(&path).unwrap();
+    let mut b = Simulation::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(snapshot(&a), snapshot(&b));
+    let actions = [Action::Switch(1), Action::Release, Action::Serve, Action::Bin];
+    for i in 0..(30.0 / TICK) as usize {
+        let action = &actions[i % actions.len()..][..1];
+        a.step(TICK, action);
+        b.step(TICK, action);
+        assert_eq!(snapshot(&a), snapshot(&b));
+        assert_eq!(a.events(), b.events());
+    }
+    assert_eq!(a.score(), b.score());
+    assert_eq!(a.lives(), b.lives());
+    assert_eq!(a.served(), b.served());
+}
+
+/// play `frames` the way the playing scene does, with a snapshot after each frame
+fn play_frames<'a>(sim: &mut Simulation, frames: impl Iterator<Item = &'a InputFrame>) -> Vec<Vec<Vec<usize>>> {
+    let mut clock = FixedStep::new();
+    let mut pending = Vec::new();
+    let mut snapshots = Vec::new();
+    for frame in frames {
+        pending.extend(frame.actions());
+        for _ in 0..clock.advance(frame.frame_elapsed) {
+            sim.step(TICK, &pending);
+            pending.clear();
+        }
+        snapshots.push(snapshot(sim));
+    }
+    snapshots
+}
+
+#[test]
+fn a_replay_plays_back_the_same_game() {
+    // uneven frame times, and a button every few frames
+    let frames: Vec<InputFrame> = (0..3000).map(|i| InputFrame {
+        frame_elapsed: if i % 3 == 0 { 1.0 / 45.0 } else { 1.0 / 60.0 },
+        left: i % 40 == 5,
+        right: i % 17 == 0,
+        down: i % 7 == 0,
+        up: i % 29 == 3,
+        serve: i % 23 == 0,
+        hold: i % 31 == 0,
+        undo: i % 53 == 0,
+        select: i % 37 == 0,
+        ..InputFrame::default()
+    }).collect();
+
+    let mut replay = Replay::new(11);
+    let mut recorded = Simulation::from_seed(replay.seed);
+    for frame in frames.iter() {
+        replay.record(*frame);
+    }
+    let snapshots = play_frames(&mut recorded, frames.iter());
+
+    let path = std::env::temp_dir().join(format!("deli_cat_essen_replay_{}.json", std::process::id()));
+    replay.save(&path).unwrap();
+    let loaded = Replay::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded.frames, frames);
+
+    let mut played = Simulation::from_seed(loaded.seed);
+    assert_eq!(play_frames(&mut played, loaded.frames.iter()), snapshots);
+    assert_eq!(played.score(), recorded.score());
+    assert_eq!(played.lives(), recorded.lives());
+    assert_eq!(played.served(), recorded.served());
+    assert_eq!(played.machine().active(), recorded.machine().active());
+}